@@ -1,43 +1,97 @@
-use bevy::{prelude::*, window::PrimaryWindow};
-use bevy_egui::{EguiContext, EguiPlugin};
+use bevy::prelude::*;
+use bevy_egui::EguiPlugin;
 use egui::Ui;
-use egui_dock::{DockArea, DockState};
+use egui_dock::DockState;
 use helium_framework::{
-    menu::{show_menu_ui, Button, Custom, MenuExt},
+    menu::{
+        context::{ContextMenuExt, ContextMenuId, ContextTarget, CLOSE_TAB},
+        search::menu_search_ui,
+        Button, Custom, Dynamic, DynamicEntry, MenuExt,
+    },
     prelude::*,
-    tab_system::{HeDockState, HeTabViewer, TabRegistrationExt, TabRegistry},
-    widgets::{dock_button, widget},
+    shell::{
+        status::{StatusBarExt, StatusSegment},
+        EditorShellExt, EditorShellPlugin, PanelSide,
+    },
+    tab_system::{HeDockState, TabRegistrationExt, TabRegistry},
+    widgets::{dock_button, keybinding_editor, widget, workspace_menu},
+    workspace::WorkspaceExt,
 };
 fn main() {
     let mut app = App::new();
     app.add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin)
-        .add_plugins(HeliumFramework)
+        .add_plugins((HeliumFramework, EditorShellPlugin))
         .insert_resource(HeDockState(DockState::new(vec!["default".into()])));
     app.add_event::<ButtonClicked>();
     app.register_action("maximize", "show mouse, events", it_works)
         .register_action("basic.log_clicked", "log click times", log_button_clicked)
-        .register_action("quit", "quit", || std::process::exit(0));
+        .register_action("quit", "quit", |mut exit: EventWriter<AppExit>| {
+            exit.send(AppExit::Success);
+        });
     app.register_tab("default", "Default", default_tab, || true)
         .register_tab("default2", "Default2", default_tab, || true)
         .register_tab("default3", "Default3", default_tab, || true)
         .register_tab("default4", "Default4", default_tab, || true)
-        .register_tab("default5", "Default5", default_tab, || true);
-    app.register_hotkey("maximize", [Hotkey::new_global([KeyCode::ControlLeft, KeyCode::KeyM])]);
+        .register_tab("default5", "Default5", default_tab, || true)
+        .register_tab("keybindings", "Keybindings", keybinding_editor, || true)
+        .add_shell_dock_panel("bottom", PanelSide::Bottom, vec!["default5".into()])
+        .add_status_segment("basic.hotkey_hint", StatusSegment::new(hotkey_hint));
+    app.register_workspace("single", DockState::new(vec!["default".into()]))
+        .register_workspace(
+            "many",
+            DockState::new(vec!["default2".into(), "default3".into(), "default4".into()]),
+        );
+    app.register_hotkey(
+        "quit",
+        [Hotkey::new_global([InputKey::from(Modifier::CmdOrCtrl), KeyCode::KeyQ.into()])],
+    );
+    app.register_hotkey(
+        "maximize",
+        [
+            Hotkey::new_global([KeyCode::ControlLeft, KeyCode::KeyM]),
+            Hotkey::new_global([InputKey::from(KeyCode::AltLeft), MouseButton::Middle.into()]),
+        ],
+    );
+    app.register_hotkey(
+        "basic.log_clicked",
+        [Hotkey::new_chord(
+            [[KeyCode::ControlLeft, KeyCode::KeyK], [KeyCode::ControlLeft, KeyCode::KeyL]],
+            || true,
+        )],
+    );
     app.menu_context(|mut ctx| {
-        ctx.with_sub_menu("file", "File".into(), 0, |mut ctx| {
-            ctx.add("quit", "Quit".into(), Button::new("quit"), 0);
+        ctx.with_sub_menu("file", "&File".into(), 0, |mut ctx| {
+            ctx.add("quit", "&Quit".into(), Button::new("quit").with_tooltip(), 0);
         });
-        ctx.with_sub_menu("window", "Window".into(), 1, |mut ctx| {
+        ctx.with_sub_menu("window", "&Window".into(), 1, |mut ctx| {
             ctx.add(
                 "win",
                 "".into(),
                 Custom(Box::new(|ui, world, _| widget(world,ui,dock_button))),
                 0,
             );
+            ctx.with_sub_menu("close", "&Close tab".into(), 1, |mut ctx| {
+                ctx.add("opened", "".into(), Dynamic::new(close_tab_entries)
+                    .rebuild_when(resource_changed::<HeDockState>), 0);
+            });
+        });
+        ctx.with_sub_menu("workspace", "Wor&kspace".into(), 2, |mut ctx| {
+            ctx.add(
+                "list",
+                "".into(),
+                Custom(Box::new(|ui, world, _| widget(world, ui, workspace_menu))),
+                0,
+            );
         });
+        ctx.with_sub_menu("help", "&Help".into(), 3, |mut ctx| {
+            ctx.add("search", "".into(), Custom(Box::new(menu_search_ui)), 0);
+        });
+    });
+    app.context_menu(ContextMenuId::Tab("default".into()), |mut ctx| {
+        ctx.add("log", "Log clicks".into(), Button::new("basic.log_clicked"), 0);
+        ctx.add("maximize", "Maximize".into(), Button::new("maximize"), 1);
     });
-    app.add_systems(Update, egui_main);
     app.run();
 }
 #[derive(Event)]
@@ -52,6 +106,17 @@ fn log_button_clicked(clickbutton: EventReader<ButtonClicked>) {
     info!("{}", clickbutton.len());
 }
 
+fn close_tab_entries(state: Res<HeDockState>, registry: Res<TabRegistry>) -> Vec<DynamicEntry> {
+    state
+        .0
+        .iter_all_tabs()
+        .map(|(_, tab)| {
+            let title = registry.get(tab).map_or_else(|| tab.to_string(), |t| t.title().into());
+            DynamicEntry::new(title, CLOSE_TAB).with_input(ContextTarget::Tab(tab.clone()))
+        })
+        .collect()
+}
+
 fn default_tab(
     In(mut ui): In<Ui>,
     mut clickbutton: EventWriter<ButtonClicked>,
@@ -71,25 +136,6 @@ fn default_tab(
     }
 }
 
-fn egui_main(world: &mut World) {
-    let mut egui_context = world.query_filtered::<&mut EguiContext, With<PrimaryWindow>>();
-    let mut binding = egui_context.single_mut(world);
-    let ctx = &binding.get_mut().clone();
-    egui::TopBottomPanel::top("menu").show(ctx, |ui| {
-        ui.horizontal(|ui| {
-            show_menu_ui(ui, world);
-            ui.label("Press ctrl+m to trigger hotkey")
-        });
-    });
-    world.resource_scope(|world: &mut World, mut registry: Mut<'_, TabRegistry>| {
-        world.resource_scope(|world: &mut World, mut state: Mut<'_, HeDockState>| {
-            DockArea::new(&mut state.0).show(
-                ctx,
-                &mut HeTabViewer {
-                    registry: &mut registry,
-                    world,
-                },
-            );
-        })
-    })
+fn hotkey_hint(In(mut ui): In<Ui>) {
+    ui.label("Press ctrl+m to trigger hotkey");
 }
@@ -1,17 +1,21 @@
 use std::any::type_name;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use bevy::reflect::{TypeInfo, Typed};
+use bevy::reflect::{FromReflect, ReflectRef, TypeInfo, Typed};
 use bevy::utils::HashMap;
 use egui::mutex::Mutex;
 use snafu::Snafu;
 
+use crate::undo::{UndoRecord, UndoStack};
 use crate::utils::identifier::Identifier;
 
 pub struct BoxedStorage {
-    boxed_action: Box<dyn DynActionStorage>,
+    boxed_action: Arc<dyn DynActionStorage>,
+    /// Present for actions registered with [`ActionsExt::register_undoable_action`].
+    undo: Option<Arc<dyn DynActionStorage>>,
     description: ActionDescription,
 }
 
@@ -22,7 +26,26 @@ pub struct ActionDescription {
 
 impl BoxedStorage {
     fn get_command(&self, input: Box<dyn Reflect>) -> Result<BoxedFn, String> {
-        self.boxed_action.get_command(input)
+        let Some(undo) = &self.undo else {
+            return self.boxed_action.get_command(input);
+        };
+        let record = UndoRecord::new(
+            self.description.to_string(),
+            input.clone_value(),
+            Arc::clone(&self.boxed_action),
+            Arc::clone(undo),
+        );
+        let command = self.boxed_action.get_command(input)?;
+        Ok(Box::new(move |world| {
+            let output = command(world);
+            // A failed action changed nothing, so there is nothing to undo.
+            if failure_message(&*output).is_none() {
+                if let Some(mut stack) = world.get_resource_mut::<UndoStack>() {
+                    stack.push(record);
+                }
+            }
+            output
+        }))
     }
     pub fn get_description(&self) -> &str {
         &self.description
@@ -30,13 +53,48 @@ impl BoxedStorage {
     pub fn input_type_info(&self) -> &'static TypeInfo {
         self.boxed_action.input_type_info()
     }
+    pub fn output_type_info(&self) -> &'static TypeInfo {
+        self.boxed_action.output_type_info()
+    }
+    pub fn is_undoable(&self) -> bool {
+        self.undo.is_some()
+    }
 }
 
 pub type ActionId = Identifier;
 
-pub trait ActionArgument: Reflect + Typed {}
+/// Input of an action. Besides the exact type, any value that [`FromReflect`] accepts
+/// (for example a dynamic value built from its [`TypeInfo`]) can be passed to the action.
+///
+/// `FromReflect` is required because the argument editor builds tuples, lists and structs as dynamic values,
+/// and most std types do not register `ReflectFromReflect`, so the conversion cannot be looked up at runtime.
+/// `#[derive(Reflect)]` implements it unless `#[reflect(from_reflect = false)]` is given.
+pub trait ActionArgument: FromReflect + Typed {}
+
+impl<T> ActionArgument for T where T: FromReflect + Typed {}
+
+/// Output of an action, returned to the caller as a `Box<dyn Reflect>`.
+/// Actions returning `Result<T, E>` are considered failed when they return `Err`,
+/// see [`failure_message`].
+pub trait ActionOutput: Reflect + Typed {}
+
+impl<T> ActionOutput for T where T: Reflect + Typed {}
 
-impl<T> ActionArgument for T where T: Reflect + Typed {}
+/// Returns the formatted error if `output` is the `Err` variant of a `Result`.
+pub fn failure_message(output: &dyn Reflect) -> Option<String> {
+    let ReflectRef::Enum(output) = output.reflect_ref() else {
+        return None;
+    };
+    let is_result = output.get_represented_type_info().is_some_and(|info| {
+        let path = info.type_path_table();
+        path.module_path() == Some("core::result") && path.ident() == Some("Result")
+    });
+    (is_result && output.variant_name() == "Err").then(|| {
+        output
+            .field_at(0)
+            .map_or_else(String::new, |err| format!("{err:?}"))
+    })
+}
 
 #[derive(Resource, Default, Deref)]
 pub struct ActionRegistry(HashMap<ActionId, BoxedStorage>);
@@ -47,49 +105,86 @@ impl ActionRegistry {
         id: &ActionId,
         input: R,
         world: &mut World,
-    ) -> Result<(), ActionError> {
-        self.0
+    ) -> Result<Box<dyn Reflect>, ActionError> {
+        self.run_instant_dyn(id, Box::new(input), world)
+    }
+    /// Same as [`ActionRegistry::run_instant`], but the input type is only known at runtime.
+    pub fn run_instant_dyn(
+        &mut self,
+        id: &ActionId,
+        input: Box<dyn Reflect>,
+        world: &mut World,
+    ) -> Result<Box<dyn Reflect>, ActionError> {
+        let found_type_name = input.reflect_type_path().to_owned();
+        Ok(self
+            .0
             .get(id)
             .ok_or(ActionError::NotFound { id: id.to_string() })?
-            .get_command(Box::new(input))
+            .get_command(input)
             .map_err(|expected| ActionError::MismatchInput {
                 expected_type_name: expected,
-                found_type_name: type_name::<R>().to_owned(),
-            })?(world);
-        Ok(())
+                found_type_name,
+            })?(world))
     }
 }
 
-type BoxedFn = Box<dyn FnOnce(&mut World) + Send + Sync + 'static>;
+pub(crate) type BoxedFn = Box<dyn FnOnce(&mut World) -> Box<dyn Reflect> + Send + Sync + 'static>;
 
 pub trait DynActionStorage: Send + Sync {
     fn get_command(&self, input: Box<dyn Reflect>) -> Result<BoxedFn, String>;
     fn input_type_info(&self) -> &'static TypeInfo;
+    fn output_type_info(&self) -> &'static TypeInfo;
 }
 
-pub struct ActionStorage<Input: ActionArgument> {
-    action: Arc<Mutex<Box<dyn System<In = Input, Out = ()>>>>,
+pub struct ActionStorage<Input: ActionArgument, Output: ActionOutput> {
+    action: Arc<Mutex<Box<dyn System<In = Input, Out = Output>>>>,
 }
 
-impl<Input: ActionArgument> DynActionStorage for ActionStorage<Input> {
-    fn get_command(
-        &self,
-        input: Box<dyn Reflect>,
-    ) -> Result<Box<dyn FnOnce(&mut World) + Send + Sync + 'static>, String> {
+impl<Input: ActionArgument, Output: ActionOutput> DynActionStorage
+    for ActionStorage<Input, Output>
+{
+    fn get_command(&self, input: Box<dyn Reflect>) -> Result<BoxedFn, String> {
         let owned_action = Arc::clone(&self.action);
-        let input = *input
-            .into_any()
-            .downcast::<Input>()
-            .map_err(|_| type_name::<Input>().to_string())?;
+        let input = match input.downcast::<Input>() {
+            Ok(input) => *input,
+            Err(input) => {
+                Input::from_reflect(&*input).ok_or_else(|| type_name::<Input>().to_string())?
+            }
+        };
         Ok(Box::new(move |world| {
             let lock = &mut owned_action.lock();
-            lock.run(input, world);
+            let output = lock.run(input, world);
             lock.apply_deferred(world);
+            Box::new(output) as Box<dyn Reflect>
         }))
     }
     fn input_type_info(&self) -> &'static TypeInfo {
         Input::type_info()
     }
+    fn output_type_info(&self) -> &'static TypeInfo {
+        Output::type_info()
+    }
+}
+
+/// Identifies an action run through [`Actions::run_action`],
+/// its output is delivered later with an [`ActionFinished`] event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ActionHandle(u64);
+
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Event)]
+pub struct ActionFinished {
+    pub handle: ActionHandle,
+    pub action: ActionId,
+    pub output: Box<dyn Reflect>,
+}
+
+impl ActionFinished {
+    /// Same as [`failure_message`] on the output.
+    pub fn failure_message(&self) -> Option<String> {
+        failure_message(&*self.output)
+    }
 }
 
 #[derive(SystemParam)]
@@ -103,20 +198,29 @@ impl Actions<'_, '_> {
         &mut self,
         id: &ActionId,
         input: I,
-    ) -> Result<(), ActionError> {
+    ) -> Result<ActionHandle, ActionError> {
         if self.storages.0.contains_key(id) {
-            self.commands.add(
-                self.storages
-                    .0
-                    .get(id)
-                    .unwrap()
-                    .get_command(Box::new(input))
-                    .map_err(|expected_type_name| ActionError::MismatchInput {
-                        expected_type_name,
-                        found_type_name: type_name::<I>().into(),
-                    })?,
-            );
-            Ok(())
+            let command = self
+                .storages
+                .0
+                .get(id)
+                .unwrap()
+                .get_command(Box::new(input))
+                .map_err(|expected_type_name| ActionError::MismatchInput {
+                    expected_type_name,
+                    found_type_name: type_name::<I>().into(),
+                })?;
+            let handle = ActionHandle(NEXT_HANDLE.fetch_add(1, Ordering::Relaxed));
+            let action = id.clone();
+            self.commands.add(move |world: &mut World| {
+                let output = command(world);
+                world.send_event(ActionFinished {
+                    handle,
+                    action,
+                    output,
+                });
+            });
+            Ok(handle)
         } else {
             Err(ActionError::NotFound { id: id.to_string() })
         }
@@ -137,31 +241,70 @@ pub enum ActionError {
 }
 
 pub trait ActionsExt {
-    fn register_action<M, In: ActionArgument>(
+    fn register_action<M, In: ActionArgument, Out: ActionOutput>(
+        &mut self,
+        id: impl Into<ActionId>,
+        description: impl Into<String>,
+        action: impl IntoSystem<In, Out, M>,
+    ) -> &mut Self;
+    /// Registers an action that is recorded in the [`UndoStack`] every time it runs.
+    /// `undo` is called with the same input as `action` to revert it.
+    fn register_undoable_action<M1, M2, In: ActionArgument, Out: ActionOutput>(
         &mut self,
         id: impl Into<ActionId>,
         description: impl Into<String>,
-        action: impl IntoSystem<In, (), M>,
+        action: impl IntoSystem<In, Out, M1>,
+        undo: impl IntoSystem<In, (), M2>,
     ) -> &mut Self;
 }
 
+fn new_storage<M, SystemInput: ActionArgument, SystemOutput: ActionOutput>(
+    world: &mut World,
+    action: impl IntoSystem<SystemInput, SystemOutput, M>,
+) -> Arc<dyn DynActionStorage> {
+    let mut system = IntoSystem::into_system(action);
+    system.initialize(world);
+    Arc::new(ActionStorage {
+        action: Arc::new(Mutex::new(Box::new(system))),
+    })
+}
+
 impl ActionsExt for App {
-    fn register_action<M, SystemInput: ActionArgument>(
+    fn register_action<M, SystemInput: ActionArgument, SystemOutput: ActionOutput>(
         &mut self,
         id: impl Into<ActionId>,
         description: impl Into<String>,
-        action: impl IntoSystem<SystemInput, (), M>,
+        action: impl IntoSystem<SystemInput, SystemOutput, M>,
     ) -> &mut Self {
         self.world_mut()
             .resource_scope(|world, mut actions: Mut<'_, ActionRegistry>| {
-                let mut system = IntoSystem::into_system(action);
-                system.initialize(world);
                 actions.0.insert(
                     id.into(),
                     BoxedStorage {
-                        boxed_action: Box::new(ActionStorage {
-                            action: Arc::new(Mutex::new(Box::new(system))),
-                        }),
+                        boxed_action: new_storage(world, action),
+                        undo: None,
+                        description: ActionDescription {
+                            description: description.into(),
+                        },
+                    },
+                );
+            });
+        self
+    }
+    fn register_undoable_action<M1, M2, SystemInput: ActionArgument, SystemOutput: ActionOutput>(
+        &mut self,
+        id: impl Into<ActionId>,
+        description: impl Into<String>,
+        action: impl IntoSystem<SystemInput, SystemOutput, M1>,
+        undo: impl IntoSystem<SystemInput, (), M2>,
+    ) -> &mut Self {
+        self.world_mut()
+            .resource_scope(|world, mut actions: Mut<'_, ActionRegistry>| {
+                actions.0.insert(
+                    id.into(),
+                    BoxedStorage {
+                        boxed_action: new_storage(world, action),
+                        undo: Some(new_storage(world, undo)),
                         description: ActionDescription {
                             description: description.into(),
                         },
@@ -176,6 +319,44 @@ pub struct ActionPlugin;
 
 impl Plugin for ActionPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ActionRegistry>();
+        app.init_resource::<ActionRegistry>()
+            .add_event::<ActionFinished>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::reflect::DynamicTuple;
+
+    use super::*;
+
+    fn checked_div(In((a, b)): In<(i32, i32)>) -> Result<i32, String> {
+        a.checked_div(b)
+            .ok_or_else(|| "division by zero".to_owned())
+    }
+
+    #[test]
+    fn test_action_output() {
+        let mut app = App::new();
+        app.add_plugins(ActionPlugin)
+            .register_action("math.div", "Divide", checked_div);
+        let world = app.world_mut();
+        world.resource_scope(|world, mut actions: Mut<ActionRegistry>| {
+            let id = "math.div".into();
+            let output = actions.run_instant(&id, (6, 3), world).unwrap();
+            assert_eq!(output.downcast_ref::<Result<i32, String>>(), Some(&Ok(2)));
+            assert_eq!(failure_message(&*output), None);
+            let output = actions.run_instant(&id, (6, 0), world).unwrap();
+            assert!(failure_message(&*output).is_some_and(|m| m.contains("division by zero")));
+            assert!(actions.run_instant(&id, (), world).is_err());
+
+            let mut dynamic = DynamicTuple::default();
+            dynamic.insert(8);
+            dynamic.insert(2);
+            let output = actions
+                .run_instant_dyn(&id, Box::new(dynamic), world)
+                .unwrap();
+            assert_eq!(output.downcast_ref::<Result<i32, String>>(), Some(&Ok(4)));
+        });
     }
 }
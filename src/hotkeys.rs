@@ -1,5 +1,11 @@
 //! Hotkey 实现。
 //! 工作方式：多个键时，最后一个键使用 [`TriggerType`] 定义的触发方式，其他键要保持按下。
+//! 依次按下多组键的和弦（如 `Ctrl+K, Ctrl+S`）见 [`chord`]。
+//! 修饰键按 [`Modifier`] 匹配，左右两侧均可，且须精确：`Ctrl+S` 不会在按下 `Ctrl+Shift+S` 时触发。
+//! 同一按键匹配多个 hotkey 时，只有 [`HotkeyScope`] 最具体、[`Hotkey::priority`] 最高、键数最多的那个触发并消耗该按键，
+//! 每次触发都会发送 [`HotkeyDispatched`] 事件。
+
+use std::cmp::Reverse;
 
 use bevy::{
     ecs::schedule::BoxedCondition,
@@ -10,9 +16,29 @@ use bevy::{
 use bevy_egui::EguiOutput;
 use smallvec::SmallVec;
 
-use crate::prelude::{ActionId, ActionRegistry};
-use crate::utils::new_condition;
+use crate::notifications::notify_action_result;
+use crate::prelude::{failure_message, ActionError, ActionId, ActionRegistry};
+use crate::tab_system::{update_focused_tab, FocusedTab, TabId};
+use crate::utils::{identifier::Identifier, new_condition};
+
+#[cfg(feature = "serde")]
+pub mod keymap;
+pub mod chord;
+pub mod input;
+pub mod recorder;
+pub mod shortcut;
+
+pub use input::{InputKey, Modifier, WheelDirection};
+pub use shortcut::{format_binding, format_stroke, ShortcutStyle};
+
+use chord::ChordState;
+
+use recorder::HotkeyRecorder;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TriggerType {
+    #[default]
     Pressed,
     Released,
     PressAndRelease,
@@ -39,142 +65,535 @@ impl RuntimeTrigger {
 }
 
 impl TriggerType {
-    fn check_trigger(
-        &self,
-        code: KeyCode,
-        input: &mut ButtonInput<KeyCode>,
-    ) -> Option<RuntimeTrigger> {
+    fn check_trigger(&self, code: InputKey, world: &mut World) -> Option<RuntimeTrigger> {
         use TriggerType::*;
         let runtime_trigger = match self {
-            Pressed if input.just_pressed(code) => Some(RuntimeTrigger::Pressed),
-            Released if input.just_released(code) => Some(RuntimeTrigger::Released),
-            PressAndRelease => input
-                .just_pressed(code)
+            Pressed if code.just_pressed(world) => Some(RuntimeTrigger::Pressed),
+            Released if code.just_released(world) => Some(RuntimeTrigger::Released),
+            PressAndRelease => code
+                .just_pressed(world)
                 .then_some(RuntimeTrigger::Pressed)
-                .or_else(|| {
-                    input
-                        .just_released(code)
-                        .then_some(RuntimeTrigger::Released)
-                }),
-            Repeat if input.pressed(code) => Some(RuntimeTrigger::Pressing),
+                .or_else(|| code.just_released(world).then_some(RuntimeTrigger::Released)),
+            Repeat if code.pressed(world) => Some(RuntimeTrigger::Pressing),
             _ => None,
         };
-        if input.just_released(code) {
+        if code.just_released(world) {
             debug!("just released {code:?};");
         }
-        input.clear_just_pressed(code);
-        input.clear_just_released(code);
         runtime_trigger
     }
 }
 
+/// Keys held together, the last one being the key that triggers.
+pub type KeyStroke = SmallVec<[InputKey; 4]>;
+
+/// The key combination of a [`Hotkey`], which unlike the condition can be saved to a keymap.
+/// A binding without keys never triggers.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyBinding {
+    pub keys: KeyStroke,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub trigger: TriggerType,
+    /// Strokes pressed one after another before `keys`, making the binding a chord like `Ctrl+K, Ctrl+S`.
+    /// Chords are dispatched by [`chord`], `trigger` applies to the last stroke.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub prefix: Vec<KeyStroke>,
+}
+
+impl KeyBinding {
+    pub fn new(keys: impl IntoIterator<Item = impl Into<InputKey>>, trigger: TriggerType) -> Self {
+        Self {
+            keys: keys.into_iter().map(Into::into).collect(),
+            trigger,
+            prefix: Vec::new(),
+        }
+    }
+    /// A binding triggered by pressing `strokes` in sequence.
+    pub fn chord<S: IntoIterator<Item = impl Into<InputKey>>>(
+        strokes: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let mut prefix: Vec<KeyStroke> = strokes
+            .into_iter()
+            .map(|stroke| stroke.into_iter().map(Into::into).collect())
+            .collect();
+        let keys = prefix.pop().unwrap_or_default();
+        Self {
+            keys,
+            trigger: TriggerType::Pressed,
+            prefix,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    pub fn is_chord(&self) -> bool {
+        !self.prefix.is_empty()
+    }
+    /// The prefix strokes followed by `keys`.
+    pub fn strokes(&self) -> impl Iterator<Item = &[InputKey]> {
+        self.prefix
+            .iter()
+            .map(KeyStroke::as_slice)
+            .chain([self.keys.as_slice()])
+    }
+    /// The shortcut text in the style of the current platform.
+    pub fn text(&self) -> String {
+        format_binding(self, ShortcutStyle::platform())
+    }
+}
+
+pub fn stroke_text(stroke: &[InputKey]) -> String {
+    format_stroke(stroke, ShortcutStyle::platform())
+}
+
+/// The stroke completed this frame: the first non-modifier input just pressed, after the held modifiers.
+pub fn pressed_stroke(world: &World) -> Option<KeyStroke> {
+    let key = InputKey::all_just_pressed(world)
+        .into_iter()
+        .find(|key| !key.is_modifier())?;
+    let mut stroke: KeyStroke = Modifier::ALL
+        .into_iter()
+        .filter(|modifier| modifier.pressed(world))
+        .map(InputKey::from)
+        .collect();
+    stroke.push(key);
+    Some(stroke)
+}
+
+/// Whether `stroke` presses `keys`, in any order except for the last key.
+/// Modifiers are compared as [`Modifier`]s.
+pub fn stroke_matches(stroke: &[InputKey], keys: &[InputKey]) -> bool {
+    let stroke: KeyStroke = stroke.iter().map(|key| key.normalized()).collect();
+    let keys: KeyStroke = keys.iter().map(|key| key.normalized()).collect();
+    match (stroke.split_last(), keys.split_last()) {
+        (Some((last1, held1)), Some((last2, held2))) => {
+            last1 == last2
+                && held1.len() == held2.len()
+                && held1.iter().all(|key| held2.contains(key))
+        }
+        _ => false,
+    }
+}
+
+pub fn is_modifier(code: KeyCode) -> bool {
+    Modifier::from_key(code).is_some()
+}
+
+/// Whether the pressed modifiers are exactly the ones in `held`.
+/// `last` may be a modifier too, and is allowed either pressed or not to support release triggers.
+fn modifiers_exact(held: &[InputKey], last: InputKey, world: &World) -> bool {
+    Modifier::ALL.into_iter().all(|modifier| {
+        last.modifier() == Some(modifier)
+            || modifier.pressed(world) == held.iter().any(|key| key.modifier() == Some(modifier))
+    })
+}
+
+/// Where a hotkey applies. Hotkeys of the focused tab shadow those of a tab prefix,
+/// which shadow global ones.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum HotkeyScope {
+    #[default]
+    Global,
+    /// Only while the tab is focused.
+    Tab(TabId),
+    /// While a tab whose id starts with the prefix is focused, the longest prefix wins.
+    TabPrefix(Identifier),
+}
+
+impl HotkeyScope {
+    pub fn is_active(&self, focused: Option<&TabId>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Tab(tab) => focused == Some(tab),
+            Self::TabPrefix(prefix) => focused.is_some_and(|tab| tab.starts_with(prefix)),
+        }
+    }
+    /// More specific scopes rank higher.
+    fn rank(&self) -> usize {
+        match self {
+            Self::Global => 0,
+            // An empty prefix matches every tab, so it still ranks above global hotkeys.
+            Self::TabPrefix(prefix) => prefix.len() + 1,
+            Self::Tab(_) => usize::MAX,
+        }
+    }
+}
+
 pub struct Hotkey {
-    pub trigger_type: TriggerType,
+    pub binding: KeyBinding,
+    /// The binding registered from code, `None` if the hotkey was added by a keymap.
+    pub default: Option<KeyBinding>,
     pub trigger_when: BoxedCondition,
-    pub key: SmallVec<[KeyCode; 4]>,
+    pub scope: HotkeyScope,
+    /// When several hotkeys of the same scope match the same input, the one with the highest priority wins,
+    /// then the one with the most keys.
+    pub priority: i32,
 }
 const fn always() -> bool {
     true
 }
 impl Hotkey {
-    pub fn new<M>(key: impl IntoIterator<Item = KeyCode>, trigger_when: impl Condition<M>) -> Self {
+    pub fn new<M>(
+        key: impl IntoIterator<Item = impl Into<InputKey>>,
+        trigger_when: impl Condition<M>,
+    ) -> Self {
         Self::new_advanced(key, trigger_when, TriggerType::Pressed)
     }
     pub fn new_advanced<M>(
-        key: impl IntoIterator<Item = KeyCode>,
+        key: impl IntoIterator<Item = impl Into<InputKey>>,
         trigger_when: impl Condition<M>,
         trigger_type: TriggerType,
     ) -> Self {
+        let binding = KeyBinding::new(key, trigger_type);
         Self {
-            trigger_type,
+            default: Some(binding.clone()),
+            binding,
             trigger_when: new_condition(trigger_when),
-            key: key.into_iter().collect(),
+            scope: HotkeyScope::Global,
+            priority: 0,
         }
     }
-    pub fn new_global(key: impl IntoIterator<Item = KeyCode>) -> Self {
+    /// A global hotkey that is not part of the code defaults.
+    pub fn from_binding(binding: KeyBinding) -> Self {
+        Self {
+            binding,
+            default: None,
+            trigger_when: new_condition(always),
+            scope: HotkeyScope::Global,
+            priority: 0,
+        }
+    }
+    /// Whether the binding has been changed from the default, or the hotkey has no default.
+    pub fn is_modified(&self) -> bool {
+        self.default.as_ref() != Some(&self.binding)
+    }
+    pub fn reset(&mut self) {
+        if let Some(default) = &self.default {
+            self.binding = default.clone();
+        }
+    }
+    /// The keys of the current binding, the last stroke for a chord.
+    pub fn key(&self) -> &KeyStroke {
+        &self.binding.keys
+    }
+    pub fn trigger_type(&self) -> TriggerType {
+        self.binding.trigger
+    }
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+    pub fn with_scope(mut self, scope: HotkeyScope) -> Self {
+        self.scope = scope;
+        self
+    }
+    /// Shorthand for [`HotkeyScope::Tab`].
+    pub fn in_tab(self, tab: impl Into<TabId>) -> Self {
+        self.with_scope(HotkeyScope::Tab(tab.into()))
+    }
+    fn rank(&self) -> (usize, i32, usize) {
+        let keys = self.binding.strokes().map(<[InputKey]>::len).sum();
+        (self.scope.rank(), self.priority, keys)
+    }
+    pub fn new_global(key: impl IntoIterator<Item = impl Into<InputKey>>) -> Self {
         Self::new(key, always)
     }
+    /// A chord hotkey, see [`KeyBinding::chord`].
+    pub fn new_chord<M, S: IntoIterator<Item = impl Into<InputKey>>>(
+        strokes: impl IntoIterator<Item = S>,
+        trigger_when: impl Condition<M>,
+    ) -> Self {
+        let binding = KeyBinding::chord(strokes);
+        Self {
+            default: Some(binding.clone()),
+            binding,
+            trigger_when: new_condition(trigger_when),
+            scope: HotkeyScope::Global,
+            priority: 0,
+        }
+    }
     /// 在应用于 `world` 前一定要先 `initialize`.
     pub fn initialize(&mut self, world: &mut World) {
         self.trigger_when.initialize(world);
     }
 
-    pub fn keyboard_trigger(&self, world: &mut World) -> Option<RuntimeTrigger> {
-        if self.key.is_empty() {
+    /// Checks the binding against every input source, not only the keyboard.
+    /// The input is not consumed, the dispatch does it for the hotkey that wins.
+    pub fn input_trigger(&self, world: &mut World) -> Option<RuntimeTrigger> {
+        let keys = &self.binding.keys;
+        let (last, held) = keys.split_last()?;
+        if self.binding.is_chord()
+            || !held.iter().all(|code| code.pressed(world))
+            || !modifiers_exact(held, *last, world)
+        {
             return None;
         }
-        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
-        let mut other_all_pressed = true;
-        for code in self.key.iter().take(self.key.len() - 1).copied() {
-            other_all_pressed &= input.pressed(code);
-        }
-        other_all_pressed
-            .then(|| {
-                self.trigger_type
-                    .check_trigger(*self.key.last().unwrap(), &mut input)
-            })
-            .flatten()
+        self.binding.trigger.check_trigger(*last, world)
     }
-    pub fn trigger_result(&mut self, world: &mut World) -> Option<RuntimeTrigger> {
+    #[deprecated = "checks every input source, renamed to `input_trigger`"]
+    pub fn keyboard_trigger(&self, world: &mut World) -> Option<RuntimeTrigger> {
+        self.input_trigger(world)
+    }
+    /// Whether the scope is active, the condition holds and the keys are not meant for a focused text field.
+    pub fn is_enabled(&mut self, world: &mut World) -> bool {
+        let focused = world.get_resource::<FocusedTab>().and_then(|f| f.0.as_ref());
+        if !self.scope.is_active(focused) {
+            return false;
+        }
         let not_editing_text = !world
             .query_filtered::<&EguiOutput, With<PrimaryWindow>>()
             .get_single(world)
-            .map_or(false, |e| e.platform_output.mutable_text_under_cursor);
-        let has_modifier = self.key.contains(&KeyCode::AltLeft)
-            || self.key.contains(&KeyCode::AltRight)
-            || self.key.contains(&KeyCode::ControlLeft)
-            || self.key.contains(&KeyCode::ControlRight);
-
-        (self.trigger_when.run_readonly((), world) && (not_editing_text || has_modifier))
-            .then(|| self.keyboard_trigger(world))
+            .is_ok_and(|e| e.platform_output.mutable_text_under_cursor);
+        let keys = self.binding.strokes().next().unwrap_or_default();
+        let has_modifier = keys
+            .iter()
+            .any(|key| key.modifier().is_some_and(|m| m != Modifier::Shift));
+        let not_keyboard = !matches!(keys.last(), Some(InputKey::Keyboard(_)));
+
+        self.trigger_when.run_readonly((), world)
+            && (not_editing_text || has_modifier || not_keyboard)
+    }
+    pub fn trigger_result(&mut self, world: &mut World) -> Option<RuntimeTrigger> {
+        self.is_enabled(world)
+            .then(|| self.input_trigger(world))
             .flatten()
     }
 
     pub fn hotkey_text(&self) -> String {
-        self.key
-            .iter()
-            .map(|k| format!("{k:?}"))
-            .collect::<Vec<_>>()
-            .join("+")
+        self.binding.text()
     }
 }
 
 #[derive(Resource, Default, Deref)]
 pub struct HotkeyRegistry(HashMap<ActionId, SmallVec<[Hotkey; 3]>>);
 
+impl HotkeyRegistry {
+    /// Restores the bindings registered in code, removing hotkeys added by keymaps.
+    pub fn reset_to_defaults(&mut self) {
+        self.0.values_mut().for_each(reset_listeners);
+    }
+    /// Like [`Self::reset_to_defaults`], only for the hotkeys of `id`.
+    pub fn reset_action(&mut self, id: &ActionId) {
+        if let Some(listeners) = self.0.get_mut(id) {
+            reset_listeners(listeners);
+        }
+    }
+    /// Replaces the keys of the `index`-th hotkey of `id`, or adds a global hotkey if there is none.
+    pub fn set_keys(
+        &mut self,
+        id: &ActionId,
+        index: Option<usize>,
+        keys: impl IntoIterator<Item = InputKey>,
+        world: &mut World,
+    ) {
+        let listeners = self.0.entry(id.clone()).or_default();
+        match index.and_then(|index| listeners.get_mut(index)) {
+            Some(hotkey) => {
+                hotkey.binding.keys = keys.into_iter().collect();
+                hotkey.binding.prefix.clear();
+            }
+            None => {
+                let mut hotkey =
+                    Hotkey::from_binding(KeyBinding::new(keys, TriggerType::default()));
+                hotkey.initialize(world);
+                listeners.push(hotkey);
+            }
+        }
+    }
+    /// Unbinds the `index`-th hotkey of `id`. Code defaults are kept with an empty binding,
+    /// so that [`Self::reset_action`] can bring them back.
+    pub fn remove_binding(&mut self, id: &ActionId, index: usize) {
+        let Some(listeners) = self.0.get_mut(id) else {
+            return;
+        };
+        match listeners.get_mut(index) {
+            Some(hotkey) if hotkey.default.is_some() => hotkey.binding.keys.clear(),
+            Some(_) => {
+                listeners.remove(index);
+            }
+            None => {}
+        }
+    }
+    /// The text of the first binding of `id`, to show next to menu items.
+    /// Global bindings come first, since the others only work while a tab is focused.
+    pub fn shortcut_text(&self, id: &ActionId) -> Option<String> {
+        let bound = || {
+            self.get(id)
+                .into_iter()
+                .flatten()
+                .filter(|hotkey| !hotkey.binding.is_empty())
+        };
+        bound()
+            .find(|hotkey| hotkey.scope == HotkeyScope::Global)
+            .or_else(|| bound().next())
+            .map(Hotkey::hotkey_text)
+    }
+    /// Bindings shared by several actions in the same scope.
+    pub fn conflicts(&self) -> Vec<HotkeyConflict> {
+        let mut by_binding: HashMap<(&HotkeyScope, &KeyBinding), Vec<ActionId>> =
+            HashMap::default();
+        for (id, listeners) in &self.0 {
+            for hotkey in listeners.iter().filter(|hotkey| !hotkey.binding.is_empty()) {
+                let actions = by_binding
+                    .entry((&hotkey.scope, &hotkey.binding))
+                    .or_default();
+                if !actions.contains(id) {
+                    actions.push(id.clone());
+                }
+            }
+        }
+        let mut conflicts: Vec<_> = by_binding
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|((scope, binding), mut actions)| {
+                actions.sort_by_cached_key(ToString::to_string);
+                HotkeyConflict {
+                    scope: scope.clone(),
+                    binding: binding.clone(),
+                    actions,
+                }
+            })
+            .collect();
+        conflicts.sort_by_cached_key(|conflict| conflict.actions[0].to_string());
+        conflicts
+    }
+}
+
+fn reset_listeners(listeners: &mut SmallVec<[Hotkey; 3]>) {
+    listeners.retain(|hotkey| hotkey.default.is_some());
+    listeners.iter_mut().for_each(Hotkey::reset);
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct HotkeyConflict {
+    pub scope: HotkeyScope,
+    pub binding: KeyBinding,
+    pub actions: Vec<ActionId>,
+}
+
 pub struct HotkeyPlugin;
 
 impl Plugin for HotkeyPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<HotkeyRegistry>();
+        app.init_resource::<HotkeyRegistry>()
+            .init_resource::<HotkeyRecorder>()
+            .init_resource::<ChordState>()
+            .init_resource::<ButtonInput<WheelDirection>>()
+            .add_event::<HotkeyDispatched>()
+            .add_systems(
+                Update,
+                chord::show_pending_chord.in_set(chord::PendingChordPopupSet),
+            );
+        app.add_systems(
+            PreUpdate,
+            (input::update_wheel_input, recorder::record_hotkey)
+                .chain()
+                .after(bevy::input::InputSystem),
+        );
         app.add_systems(
             PostUpdate,
-            dispatch_hotkey.after(bevy_egui::EguiSet::ProcessOutput),
+            dispatch_hotkey
+                .after(bevy_egui::EguiSet::ProcessOutput)
+                .after(update_focused_tab),
         );
     }
 }
 
+/// Sent for every hotkey that ran its action.
+#[derive(Event, Clone, Debug)]
+pub struct HotkeyDispatched {
+    pub action: ActionId,
+    pub binding: KeyBinding,
+    pub trigger: RuntimeTrigger,
+    /// Why the action could not run or returned an `Err`.
+    pub failure: Option<String>,
+}
+
+/// A hotkey matching the current input, competing with the others matching the same key.
+#[derive(Clone, Debug)]
+struct Candidate {
+    id: ActionId,
+    binding: KeyBinding,
+    rank: (usize, i32, usize),
+}
+
+impl Candidate {
+    fn new(id: &ActionId, hotkey: &Hotkey) -> Self {
+        Self {
+            id: id.clone(),
+            binding: hotkey.binding.clone(),
+            rank: hotkey.rank(),
+        }
+    }
+    /// Ties are broken by action id, so that the winner does not depend on the registration order.
+    fn beats(&self, other: &Self) -> bool {
+        (self.rank, Reverse(self.id.to_string())) > (other.rank, Reverse(other.id.to_string()))
+    }
+    fn run(self, world: &mut World, trigger: RuntimeTrigger) {
+        let result = run_hotkey_action(world, &self.id, trigger);
+        notify_action_result(world, &self.id, &result);
+        let failure = match &result {
+            Ok(output) => failure_message(&**output),
+            Err(err) => Some(err.to_string()),
+        };
+        world.send_event(HotkeyDispatched {
+            action: self.id,
+            binding: self.binding,
+            trigger,
+            failure,
+        });
+    }
+}
+
 fn dispatch_hotkey(world: &mut World) {
+    if world.resource::<HotkeyRecorder>().is_recording() {
+        return;
+    }
     world.resource_scope(|world: &mut World, mut hotkeys: Mut<'_, HotkeyRegistry>| {
+        chord::dispatch_chords(world, &mut hotkeys);
+        let mut winners: HashMap<InputKey, (Candidate, RuntimeTrigger)> = HashMap::default();
         for (id, listeners) in hotkeys.0.iter_mut() {
             for listener in listeners {
-                if let Some(trigger) = listener.trigger_result(world) {
-                    // todo: error handling
-                    world
-                        .resource_scope(
-                            |world: &mut World, mut actions: Mut<'_, ActionRegistry>| {
-                                actions
-                                    .run_instant(id, trigger, world)
-                                    .or_else(|_| actions.run_instant(id, (), world))
-                            },
-                        )
-                        .expect("encountered err (todo handle this)");
+                let Some(trigger) = listener.trigger_result(world) else {
+                    continue;
+                };
+                let key = listener.binding.keys.last().unwrap().normalized();
+                let candidate = Candidate::new(id, listener);
+                match winners.get(&key) {
+                    Some((winner, _)) if !candidate.beats(winner) => {}
+                    _ => {
+                        winners.insert(key, (candidate, trigger));
+                    }
                 }
             }
         }
+        let mut winners: Vec<_> = winners.into_iter().collect();
+        winners.sort_by_cached_key(|(_, (candidate, _))| candidate.id.to_string());
+        for (key, (candidate, trigger)) in winners {
+            key.clear(world, true);
+            candidate.run(world, trigger);
+        }
     });
 }
 
+/// Runs `id` with the trigger as input, or without input if it does not take one.
+fn run_hotkey_action(
+    world: &mut World,
+    id: &ActionId,
+    trigger: RuntimeTrigger,
+) -> Result<Box<dyn Reflect>, ActionError> {
+    world.resource_scope(|world: &mut World, mut actions: Mut<'_, ActionRegistry>| {
+        match actions.run_instant(id, trigger, world) {
+            Err(ActionError::MismatchInput { .. }) => actions.run_instant(id, (), world),
+            result => result,
+        }
+    })
+}
+
 pub trait HotkeysExt {
     fn register_hotkey(
         &mut self,
@@ -205,3 +624,98 @@ impl HotkeysExt for App {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::action::{ActionPlugin, ActionsExt};
+
+    /// The ids of the actions run by a [`test_app`], in order.
+    #[derive(Resource, Default)]
+    pub(super) struct Ran(pub Vec<&'static str>);
+
+    /// An app with a fake keyboard and an action for each of `actions`, which records its id in [`Ran`].
+    pub(super) fn test_app(actions: &[&'static str]) -> App {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, HotkeyPlugin))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Ran>();
+        for &id in actions {
+            app.register_action(id, id, move |mut ran: ResMut<Ran>| ran.0.push(id));
+        }
+        app
+    }
+
+    /// Releases the keys held so far, presses `keys` and dispatches, returning the dispatched hotkeys.
+    pub(super) fn press(world: &mut World, keys: &[KeyCode]) -> Vec<HotkeyDispatched> {
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.clear();
+        input.release_all();
+        for key in keys {
+            input.press(*key);
+        }
+        dispatch_hotkey(world);
+        world
+            .resource_mut::<Events<HotkeyDispatched>>()
+            .drain()
+            .collect()
+    }
+
+    #[test]
+    fn test_dispatch_order() {
+        use KeyCode::*;
+        let mut app = test_app(&["low", "high", "long"]);
+        app.register_hotkey("low", [Hotkey::new_global([ControlLeft, KeyS])])
+            .register_hotkey(
+                "high",
+                [Hotkey::new_global([ControlLeft, KeyS]).with_priority(1)],
+            )
+            .register_hotkey("low", [Hotkey::new_global([KeyD])])
+            .register_hotkey("long", [Hotkey::new_global([Space, KeyD])])
+            .register_hotkey("missing", [Hotkey::new_global([F5])]);
+        let world = app.world_mut();
+
+        press(world, &[ControlLeft, KeyS]);
+        press(world, &[Space, KeyD]);
+        let dispatched = press(world, &[F5]);
+        assert_eq!(dispatched[0].action, "missing".into());
+        assert!(dispatched[0].failure.is_some());
+        assert_eq!(app.world().resource::<Ran>().0, ["high", "long"]);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let mut app = test_app(&["global", "editor", "outline"]);
+        app.init_resource::<FocusedTab>()
+            .register_hotkey("global", [Hotkey::new_global([KeyCode::Delete])])
+            .register_hotkey(
+                "editor",
+                [Hotkey::new_global([KeyCode::Delete])
+                    .with_scope(HotkeyScope::TabPrefix("editor".into()))],
+            )
+            .register_hotkey(
+                "outline",
+                [
+                    Hotkey::new_global([KeyCode::Delete]).in_tab("editor.outline"),
+                    Hotkey::new_global([KeyCode::F2]),
+                ],
+            );
+        let world = app.world_mut();
+        let registry = world.resource::<HotkeyRegistry>();
+        assert!(registry.conflicts().is_empty());
+        assert_eq!(
+            registry.shortcut_text(&"outline".into()),
+            Some(stroke_text(&[KeyCode::F2.into()]))
+        );
+        let any_tab = HotkeyScope::TabPrefix(Identifier::from_iter(std::iter::empty::<String>()));
+        assert!(any_tab.rank() > HotkeyScope::Global.rank());
+        for tab in ["editor.outline", "editor.viewport", "editorial", "other"] {
+            world.resource_mut::<FocusedTab>().0 = Some(tab.into());
+            press(world, &[KeyCode::Delete]);
+        }
+        assert_eq!(
+            world.resource::<Ran>().0,
+            ["outline", "editor", "global", "global"]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Chords, hotkeys made of several strokes pressed in sequence like `Ctrl+K, Ctrl+S`.
+//!
+//! A stroke starting a chord is consumed and kept pending until the chord completes,
+//! a stroke that continues no chord is pressed, or [`ChordState::timeout`] expires.
+//! When a binding is a prefix of another one, like `Ctrl+K` and `Ctrl+K, Ctrl+S`,
+//! the shorter one runs once the chord is abandoned, as if pressed.
+//!
+//! A completed chord honours the [`TriggerType`] of its binding on its last stroke,
+//! e.g. a `Released` chord runs when the last key of the chord is released.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::Instant, window::PrimaryWindow};
+use bevy_egui::EguiContext;
+use rust_i18n::t;
+
+use super::{
+    pressed_stroke, stroke_matches, stroke_text, Candidate, HotkeyRegistry, KeyStroke,
+    RuntimeTrigger, TriggerType,
+};
+
+#[derive(Resource, Debug)]
+pub struct ChordState {
+    /// How long to wait for the next stroke of a chord.
+    pub timeout: Duration,
+    strokes: Vec<KeyStroke>,
+    last_stroke: Instant,
+    /// The completed chord whose trigger needs later frames, kept while its last key is held.
+    completed: Option<Candidate>,
+}
+
+impl Default for ChordState {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(1500),
+            strokes: Vec::new(),
+            last_stroke: Instant::now(),
+            completed: None,
+        }
+    }
+}
+
+impl ChordState {
+    pub fn is_pending(&self) -> bool {
+        !self.strokes.is_empty()
+    }
+    /// The strokes of the chord pressed so far.
+    pub fn pending(&self) -> &[KeyStroke] {
+        &self.strokes
+    }
+    pub fn pending_text(&self) -> Option<String> {
+        self.is_pending().then(|| {
+            self.strokes
+                .iter()
+                .map(|stroke| stroke_text(stroke))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+    pub fn cancel(&mut self) {
+        self.strokes.clear();
+    }
+}
+
+struct SequenceMatch {
+    /// The best hotkey whose binding is exactly the sequence.
+    exact: Option<Candidate>,
+    /// Whether the sequence starts a longer binding.
+    longer: bool,
+}
+
+fn match_sequence(
+    world: &mut World,
+    hotkeys: &mut HotkeyRegistry,
+    sequence: &[KeyStroke],
+) -> SequenceMatch {
+    let mut result = SequenceMatch {
+        exact: None,
+        longer: false,
+    };
+    for (id, listeners) in hotkeys.0.iter_mut() {
+        for hotkey in listeners.iter_mut() {
+            let binding = &hotkey.binding;
+            let len = binding.prefix.len() + 1;
+            let prefix_matches = sequence
+                .iter()
+                .zip(binding.strokes())
+                .all(|(stroke, keys)| stroke_matches(stroke, keys));
+            if binding.is_empty()
+                || len < sequence.len()
+                || !prefix_matches
+                || !hotkey.is_enabled(world)
+            {
+                continue;
+            }
+            if len == sequence.len() {
+                let candidate = Candidate::new(id, hotkey);
+                let better = match &result.exact {
+                    Some(exact) => candidate.beats(exact),
+                    None => true,
+                };
+                if better {
+                    result.exact = Some(candidate);
+                }
+            } else {
+                result.longer = true;
+            }
+        }
+    }
+    result
+}
+
+/// Runs the action bound to the abandoned chord `strokes`, if any.
+fn flush(world: &mut World, hotkeys: &mut HotkeyRegistry, strokes: &[KeyStroke]) {
+    if let Some(candidate) = match_sequence(world, hotkeys, strokes).exact {
+        candidate.run(world, RuntimeTrigger::Pressed);
+    }
+}
+
+/// Runs the completed chord again if its trigger fires on this frame, e.g. on release.
+fn trigger_completed(world: &mut World) {
+    let Some(candidate) = world.resource_mut::<ChordState>().completed.take() else {
+        return;
+    };
+    let key = *candidate.binding.keys.last().unwrap();
+    if let Some(trigger) = candidate.binding.trigger.check_trigger(key, world) {
+        key.clear(world, true);
+        candidate.clone().run(world, trigger);
+    }
+    if key.pressed(world) {
+        world.resource_mut::<ChordState>().completed = Some(candidate);
+    }
+}
+
+/// Feeds the stroke of this frame into the pending chord. Consumed strokes are cleared from the input,
+/// so that single stroke hotkeys do not trigger on them.
+pub(crate) fn dispatch_chords(world: &mut World, hotkeys: &mut HotkeyRegistry) {
+    trigger_completed(world);
+    let state = world.resource::<ChordState>();
+    if state.is_pending() && state.last_stroke.elapsed() >= state.timeout {
+        let strokes = std::mem::take(&mut world.resource_mut::<ChordState>().strokes);
+        flush(world, hotkeys, &strokes);
+    }
+    let Some(stroke) = pressed_stroke(world) else {
+        return;
+    };
+    let key = *stroke.last().unwrap();
+
+    let mut sequence = world.resource::<ChordState>().strokes.clone();
+    sequence.push(stroke.clone());
+    let mut found = match_sequence(world, hotkeys, &sequence);
+    if !found.longer && found.exact.is_none() && sequence.len() > 1 {
+        // the chord is abandoned, the stroke may start another one
+        world.resource_mut::<ChordState>().cancel();
+        flush(world, hotkeys, &sequence[..sequence.len() - 1]);
+        sequence = vec![stroke];
+        found = match_sequence(world, hotkeys, &sequence);
+    }
+
+    let consumed = found.longer || (found.exact.is_some() && sequence.len() > 1);
+    if !consumed {
+        // single stroke hotkeys are left to the regular dispatch
+        return;
+    }
+    key.clear(world, false);
+    let mut state = world.resource_mut::<ChordState>();
+    if found.longer {
+        state.strokes = sequence;
+        state.last_stroke = Instant::now();
+    } else if let Some(candidate) = found.exact {
+        state.cancel();
+        // The last key was just pressed, later triggers are checked by `trigger_completed`.
+        let trigger = match candidate.binding.trigger {
+            TriggerType::Pressed | TriggerType::PressAndRelease => Some(RuntimeTrigger::Pressed),
+            TriggerType::Repeat => Some(RuntimeTrigger::Pressing),
+            TriggerType::Released => None,
+        };
+        if candidate.binding.trigger != TriggerType::Pressed {
+            state.completed = Some(candidate.clone());
+        }
+        if let Some(trigger) = trigger {
+            candidate.run(world, trigger);
+        }
+    }
+}
+
+/// The set of the system showing the pending chord in a popup, in [`Update`].
+/// Other plugins showing the chord themselves, like the shell's status bar, add a condition to it.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PendingChordPopupSet;
+
+pub(crate) fn show_pending_chord(
+    mut context: Query<&mut EguiContext, With<PrimaryWindow>>,
+    state: Res<ChordState>,
+) {
+    let (Some(text), Ok(mut ctx)) = (state.pending_text(), context.get_single_mut()) else {
+        return;
+    };
+    egui::Area::new("pending_chord".into())
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .interactable(false)
+        .show(ctx.get_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(t!("hotkey.chord_pending", keys = text));
+            });
+        });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hotkeys::{
+        test::{press, test_app, Ran},
+        Hotkey, HotkeysExt,
+    };
+
+    #[test]
+    fn test_chords() {
+        use KeyCode::*;
+        let mut app = test_app(&["short", "chord", "release"]);
+        app.register_hotkey("short", [Hotkey::new_global([ControlLeft, KeyK])])
+            .register_hotkey(
+                "chord",
+                [Hotkey::new_chord(
+                    [[ControlLeft, KeyK], [ControlLeft, KeyS]],
+                    || true,
+                )],
+            );
+        let mut on_release = Hotkey::new_chord([[ControlLeft, KeyJ], [ControlLeft, KeyR]], || true);
+        on_release.binding.trigger = TriggerType::Released;
+        app.register_hotkey("release", [on_release]);
+        let world = app.world_mut();
+
+        press(world, &[ControlLeft, KeyK]);
+        assert_eq!(
+            world.resource::<ChordState>().pending_text().unwrap(),
+            "Ctrl+K"
+        );
+        press(world, &[ControlLeft, KeyS]);
+        assert!(!world.resource::<ChordState>().is_pending());
+        assert_eq!(world.resource::<Ran>().0, ["chord"]);
+
+        press(world, &[ControlLeft, KeyK]);
+        press(world, &[ControlLeft, KeyX]);
+        assert!(!world.resource::<ChordState>().is_pending());
+        assert_eq!(world.resource::<Ran>().0, ["chord", "short"]);
+
+        world.resource_mut::<ChordState>().timeout = Duration::ZERO;
+        press(world, &[ControlLeft, KeyK]);
+        press(world, &[]);
+        assert_eq!(world.resource::<Ran>().0, ["chord", "short", "short"]);
+
+        world.resource_mut::<ChordState>().timeout = Duration::from_secs(60);
+        press(world, &[ControlLeft, KeyJ]);
+        press(world, &[ControlLeft, KeyR]);
+        assert_eq!(world.resource::<Ran>().0.len(), 3);
+        press(world, &[]);
+        assert_eq!(world.resource::<Ran>().0[3..], ["release"]);
+        assert!(world.resource::<ChordState>().completed.is_none());
+    }
+}
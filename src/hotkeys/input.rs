@@ -0,0 +1,322 @@
+//! Inputs a hotkey can be bound to: keyboard keys, mouse buttons, the mouse wheel and gamepad buttons.
+//!
+//! Modifier keys converted from a [`KeyCode`] become a [`Modifier`] which matches either side,
+//! use [`InputKey::Keyboard`] directly to require a specific side.
+//!
+//! Every source is read from its [`ButtonInput`] resource, a missing resource reads as never pressed.
+//! The wheel has no such resource in Bevy, [`ButtonInput<WheelDirection>`] is filled from [`MouseWheel`]
+//! events and a scroll counts as a press and a release in the same frame.
+
+use std::hash::Hash;
+
+use bevy::{input::mouse::MouseWheel, prelude::*};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputKey {
+    Keyboard(KeyCode),
+    Modifier(Modifier),
+    Mouse(MouseButton),
+    Wheel(WheelDirection),
+    /// The button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+    /// Cmd on macOS and Ctrl elsewhere, for the usual application shortcuts.
+    CmdOrCtrl,
+}
+
+impl Modifier {
+    /// The modifiers as they are pressed, without [`Modifier::CmdOrCtrl`].
+    pub const ALL: [Modifier; 4] = [Self::Ctrl, Self::Shift, Self::Alt, Self::Super];
+
+    /// Resolves [`Modifier::CmdOrCtrl`] for the current platform.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::CmdOrCtrl if cfg!(target_os = "macos") => Self::Super,
+            Self::CmdOrCtrl => Self::Ctrl,
+            modifier => modifier,
+        }
+    }
+    pub fn from_key(code: KeyCode) -> Option<Self> {
+        match code {
+            KeyCode::ControlLeft | KeyCode::ControlRight => Some(Self::Ctrl),
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => Some(Self::Shift),
+            KeyCode::AltLeft | KeyCode::AltRight => Some(Self::Alt),
+            KeyCode::SuperLeft | KeyCode::SuperRight => Some(Self::Super),
+            _ => None,
+        }
+    }
+    pub fn pressed(self, world: &World) -> bool {
+        InputKey::Modifier(self).pressed(world)
+    }
+}
+
+impl From<KeyCode> for InputKey {
+    fn from(value: KeyCode) -> Self {
+        match Modifier::from_key(value) {
+            Some(modifier) => Self::Modifier(modifier),
+            None => Self::Keyboard(value),
+        }
+    }
+}
+
+impl From<Modifier> for InputKey {
+    fn from(value: Modifier) -> Self {
+        Self::Modifier(value)
+    }
+}
+
+impl From<MouseButton> for InputKey {
+    fn from(value: MouseButton) -> Self {
+        Self::Mouse(value)
+    }
+}
+
+impl From<WheelDirection> for InputKey {
+    fn from(value: WheelDirection) -> Self {
+        Self::Wheel(value)
+    }
+}
+
+impl From<GamepadButtonType> for InputKey {
+    fn from(value: GamepadButtonType) -> Self {
+        Self::Gamepad(value)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ButtonQuery {
+    Pressed,
+    JustPressed,
+    JustReleased,
+}
+
+fn query_input<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    world: &World,
+    query: ButtonQuery,
+    matches: impl Fn(&T) -> bool,
+) -> bool {
+    let Some(input) = world.get_resource::<ButtonInput<T>>() else {
+        return false;
+    };
+    match query {
+        ButtonQuery::Pressed => input.get_pressed().any(matches),
+        ButtonQuery::JustPressed => input.get_just_pressed().any(matches),
+        ButtonQuery::JustReleased => input.get_just_released().any(matches),
+    }
+}
+
+fn clear_input<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    world: &mut World,
+    released: bool,
+    matches: impl Fn(&T) -> bool,
+) {
+    let Some(mut input) = world.get_resource_mut::<ButtonInput<T>>() else {
+        return;
+    };
+    let buttons: Vec<T> = input
+        .get_just_pressed()
+        .filter(|b| matches(b))
+        .copied()
+        .collect();
+    for button in buttons {
+        input.clear_just_pressed(button);
+    }
+    if released {
+        let buttons: Vec<T> = input
+            .get_just_released()
+            .filter(|b| matches(b))
+            .copied()
+            .collect();
+        for button in buttons {
+            input.clear_just_released(button);
+        }
+    }
+}
+
+fn just_pressed<T: Copy + Eq + Hash + Send + Sync + 'static>(world: &World) -> Vec<T> {
+    world
+        .get_resource::<ButtonInput<T>>()
+        .map(|input| input.get_just_pressed().copied().collect())
+        .unwrap_or_default()
+}
+
+impl InputKey {
+    fn query(self, world: &World, query: ButtonQuery) -> bool {
+        match self {
+            Self::Keyboard(key) => query_input(world, query, |b: &KeyCode| *b == key),
+            Self::Modifier(modifier) => query_input(world, query, |b: &KeyCode| {
+                Modifier::from_key(*b) == Some(modifier.resolve())
+            }),
+            Self::Mouse(button) => query_input(world, query, |b: &MouseButton| *b == button),
+            Self::Wheel(direction) => {
+                query_input(world, query, |b: &WheelDirection| *b == direction)
+            }
+            Self::Gamepad(ty) => query_input(world, query, |b: &GamepadButton| b.button_type == ty),
+        }
+    }
+    pub fn pressed(self, world: &World) -> bool {
+        self.query(world, ButtonQuery::Pressed)
+    }
+    pub fn just_pressed(self, world: &World) -> bool {
+        self.query(world, ButtonQuery::JustPressed)
+    }
+    pub fn just_released(self, world: &World) -> bool {
+        self.query(world, ButtonQuery::JustReleased)
+    }
+    /// Consumes the press, and the release if `released`, so that other hotkeys do not see it.
+    pub fn clear(self, world: &mut World, released: bool) {
+        match self {
+            Self::Keyboard(key) => clear_input(world, released, |b: &KeyCode| *b == key),
+            Self::Modifier(modifier) => clear_input(world, released, |b: &KeyCode| {
+                Modifier::from_key(*b) == Some(modifier.resolve())
+            }),
+            Self::Mouse(button) => clear_input(world, released, |b: &MouseButton| *b == button),
+            Self::Wheel(direction) => {
+                clear_input(world, released, |b: &WheelDirection| *b == direction)
+            }
+            Self::Gamepad(ty) => {
+                clear_input(world, released, |b: &GamepadButton| b.button_type == ty)
+            }
+        }
+    }
+    /// Every input pressed this frame, keyboard first.
+    pub fn all_just_pressed(world: &World) -> Vec<InputKey> {
+        let mut keys: Vec<InputKey> = just_pressed::<KeyCode>(world)
+            .into_iter()
+            .map(Self::from)
+            .collect();
+        keys.extend(
+            just_pressed::<MouseButton>(world)
+                .into_iter()
+                .map(Self::from),
+        );
+        keys.extend(
+            just_pressed::<WheelDirection>(world)
+                .into_iter()
+                .map(Self::from),
+        );
+        keys.extend(
+            just_pressed::<GamepadButton>(world)
+                .into_iter()
+                .map(|button| Self::from(button.button_type)),
+        );
+        keys
+    }
+    pub fn is_modifier(self) -> bool {
+        self.modifier().is_some()
+    }
+    /// The resolved modifier this input is or presses.
+    pub fn modifier(self) -> Option<Modifier> {
+        match self {
+            Self::Keyboard(key) => Modifier::from_key(key),
+            Self::Modifier(modifier) => Some(modifier.resolve()),
+            _ => None,
+        }
+    }
+    /// Turns modifier keys of either kind into resolved [`Modifier`]s, for comparison.
+    pub fn normalized(self) -> Self {
+        self.modifier().map_or(self, Self::Modifier)
+    }
+}
+
+pub(crate) fn update_wheel_input(
+    mut input: ResMut<ButtonInput<WheelDirection>>,
+    mut wheel: EventReader<MouseWheel>,
+) {
+    input.clear();
+    let mut pulse = |direction| {
+        input.press(direction);
+        input.release(direction);
+    };
+    for event in wheel.read() {
+        if event.y > 0.0 {
+            pulse(WheelDirection::Up);
+        } else if event.y < 0.0 {
+            pulse(WheelDirection::Down);
+        }
+        if event.x > 0.0 {
+            pulse(WheelDirection::Right);
+        } else if event.x < 0.0 {
+            pulse(WheelDirection::Left);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hotkeys::{
+        shortcut::{format_binding, ShortcutStyle},
+        Hotkey,
+    };
+
+    #[test]
+    fn test_mixed_inputs() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<WheelDirection>>();
+        let hotkey = Hotkey::new_global([
+            InputKey::from(KeyCode::ControlLeft),
+            WheelDirection::Up.into(),
+        ]);
+        assert_eq!(
+            format_binding(&hotkey.binding, ShortcutStyle::Text),
+            "Ctrl+Scroll Up"
+        );
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ControlLeft);
+        let mut wheel = world.resource_mut::<ButtonInput<WheelDirection>>();
+        wheel.press(WheelDirection::Up);
+        wheel.release(WheelDirection::Up);
+        assert!(hotkey.input_trigger(&mut world).is_some());
+        InputKey::from(WheelDirection::Up).clear(&mut world, true);
+        assert!(!InputKey::from(WheelDirection::Up).just_pressed(&world));
+        assert!(!InputKey::from(MouseButton::Middle).pressed(&world));
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        let hotkey = Hotkey::new_global([KeyCode::ControlLeft, KeyCode::KeyS]);
+        let press = |world: &mut World, keys: &[KeyCode]| {
+            let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+            input.reset_all();
+            for key in keys {
+                input.press(*key);
+            }
+            hotkey.input_trigger(world).is_some()
+        };
+
+        assert!(press(&mut world, &[KeyCode::ControlRight, KeyCode::KeyS]));
+        assert!(!press(
+            &mut world,
+            &[KeyCode::ControlLeft, KeyCode::ShiftLeft, KeyCode::KeyS]
+        ));
+        assert!(!press(&mut world, &[KeyCode::KeyS]));
+        assert_eq!(
+            InputKey::from(Modifier::CmdOrCtrl).normalized(),
+            InputKey::Modifier(Modifier::CmdOrCtrl.resolve())
+        );
+    }
+}
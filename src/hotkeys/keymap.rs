@@ -0,0 +1,230 @@
+//! User keymaps, bindings loaded from a RON file and merged over the hotkeys registered in code.
+//!
+//! A keymap maps an action to the full list of its bindings. The n-th binding replaces
+//! the key combination of the n-th hotkey registered for the action and keeps its condition,
+//! an empty binding or a missing position unbinds it, extra bindings become global hotkeys.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use super::{Hotkey, HotkeyRegistry, KeyBinding};
+use crate::{
+    action::{ActionId, ActionsExt},
+    notifications::notify_action_failure,
+};
+
+pub const SAVE_KEYMAP: &str = "keymap.save";
+pub const LOAD_KEYMAP: &str = "keymap.load";
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Keymap(pub IndexMap<ActionId, Vec<KeyBinding>>);
+
+impl HotkeyRegistry {
+    /// Resets to the code defaults, then applies `keymap` over them.
+    pub fn apply_keymap(&mut self, keymap: &Keymap, world: &mut World) {
+        self.reset_to_defaults();
+        for (id, bindings) in &keymap.0 {
+            let listeners = self.0.entry(id.clone()).or_default();
+            for (i, hotkey) in listeners.iter_mut().enumerate() {
+                hotkey.binding = bindings.get(i).cloned().unwrap_or_default();
+            }
+            for binding in bindings.iter().skip(listeners.len()) {
+                let mut hotkey = Hotkey::from_binding(binding.clone());
+                hotkey.initialize(world);
+                listeners.push(hotkey);
+            }
+        }
+    }
+    /// The bindings of every action that differs from the code defaults.
+    pub fn keymap(&self) -> Keymap {
+        let mut keymap: IndexMap<_, _> = self
+            .0
+            .iter()
+            .filter(|(_, listeners)| listeners.iter().any(Hotkey::is_modified))
+            .map(|(id, listeners)| {
+                let bindings = listeners
+                    .iter()
+                    .map(|hotkey| hotkey.binding.clone())
+                    .collect();
+                (id.clone(), bindings)
+            })
+            .collect();
+        keymap.sort_by_cached_key(|id, _| id.to_string());
+        Keymap(keymap)
+    }
+}
+
+#[derive(Snafu, Debug)]
+pub enum KeymapError {
+    #[snafu(display("cannot access keymap file {}: {source}", path.display()))]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("cannot serialize keymap: {source}"))]
+    SerializeRon { source: ron::Error },
+    #[snafu(display("cannot parse keymap: {source}"))]
+    DeserializeRon { source: ron::error::SpannedError },
+}
+
+pub fn load_keymap(path: &Path) -> Result<Keymap, KeymapError> {
+    let text = fs::read_to_string(path).context(IoSnafu { path })?;
+    ron::from_str(&text).context(DeserializeRonSnafu)
+}
+
+pub fn save_keymap(keymap: &Keymap, path: &Path) -> Result<(), KeymapError> {
+    let text = ron::ser::to_string_pretty(keymap, Default::default()).context(SerializeRonSnafu)?;
+    fs::write(path, text).context(IoSnafu { path })
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct KeymapConfig {
+    pub path: PathBuf,
+    pub load_on_startup: bool,
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        Self {
+            path: "keymap.ron".into(),
+            load_on_startup: true,
+        }
+    }
+}
+
+/// Handled in [`PreUpdate`], since hotkeys cannot be changed while they are dispatched.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeymapRequest {
+    Save,
+    Load,
+}
+
+fn load_and_apply(world: &mut World, path: &Path) -> Result<(), KeymapError> {
+    let keymap = load_keymap(path)?;
+    world.resource_scope(|world, mut hotkeys: Mut<HotkeyRegistry>| {
+        hotkeys.apply_keymap(&keymap, world);
+        for conflict in hotkeys.conflicts() {
+            warn!(
+                "{} is bound to several actions: {:?}",
+                conflict.binding.text(),
+                conflict.actions
+            );
+        }
+    });
+    Ok(())
+}
+
+fn load_keymap_on_startup(world: &mut World) {
+    let config = world.resource::<KeymapConfig>().clone();
+    if !config.load_on_startup || !config.path.exists() {
+        return;
+    }
+    if let Err(err) = load_and_apply(world, &config.path) {
+        warn!("keymap not loaded: {err}");
+    }
+}
+
+fn handle_keymap_requests(world: &mut World) {
+    let requests: Vec<_> = world
+        .resource_mut::<Events<KeymapRequest>>()
+        .drain()
+        .collect();
+    let path = world.resource::<KeymapConfig>().path.clone();
+    for request in requests {
+        let (id, result) = match request {
+            KeymapRequest::Save => (
+                SAVE_KEYMAP,
+                save_keymap(&world.resource::<HotkeyRegistry>().keymap(), &path),
+            ),
+            KeymapRequest::Load => (LOAD_KEYMAP, load_and_apply(world, &path)),
+        };
+        if let Err(err) = result {
+            notify_action_failure(world, &id.into(), err);
+        }
+    }
+}
+
+pub struct KeymapPlugin;
+
+impl Plugin for KeymapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeymapConfig>()
+            .add_event::<KeymapRequest>()
+            .register_action(
+                SAVE_KEYMAP,
+                "Save keymap",
+                |mut requests: EventWriter<KeymapRequest>| {
+                    requests.send(KeymapRequest::Save);
+                },
+            )
+            .register_action(
+                LOAD_KEYMAP,
+                "Reload keymap",
+                |mut requests: EventWriter<KeymapRequest>| {
+                    requests.send(KeymapRequest::Load);
+                },
+            )
+            .add_systems(PostStartup, load_keymap_on_startup)
+            .add_systems(PreUpdate, handle_keymap_requests);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hotkeys::{HotkeyPlugin, HotkeysExt, TriggerType};
+
+    #[test]
+    fn test_apply_keymap() {
+        let mut app = App::new();
+        app.add_plugins(HotkeyPlugin)
+            .register_hotkey(
+                "file.save",
+                [Hotkey::new_global([KeyCode::ControlLeft, KeyCode::KeyS])],
+            )
+            .register_hotkey(
+                "file.open",
+                [
+                    Hotkey::new_global([KeyCode::ControlLeft, KeyCode::KeyO]),
+                    Hotkey::new_global([KeyCode::F2]),
+                ],
+            );
+        let world = app.world_mut();
+        let ctrl_o = KeyBinding::new([KeyCode::ControlLeft, KeyCode::KeyO], TriggerType::Pressed);
+        let f3 = KeyBinding::new([KeyCode::F3], TriggerType::Pressed);
+        let keymap: Keymap = ron::from_str(
+            r#"{
+                "file.save": [(keys: [Modifier(Ctrl), Keyboard(KeyO)])],
+                "file.new": [(keys: [Keyboard(F3)])],
+            }"#,
+        )
+        .unwrap();
+
+        world.resource_scope(|world, mut hotkeys: Mut<HotkeyRegistry>| {
+            hotkeys.apply_keymap(&keymap, world);
+            assert_eq!(hotkeys[&ActionId::from("file.save")][0].binding, ctrl_o);
+            assert_eq!(hotkeys[&ActionId::from("file.new")][0].binding, f3);
+            assert_eq!(
+                hotkeys.conflicts()[0].actions,
+                vec!["file.open".into(), "file.save".into()]
+            );
+            assert_eq!(hotkeys.keymap(), {
+                let mut expected = keymap.clone();
+                expected.0.sort_by_cached_key(|id, _| id.to_string());
+                expected
+            });
+
+            hotkeys.reset_to_defaults();
+            assert!(hotkeys[&ActionId::from("file.new")].is_empty());
+            assert!(hotkeys.keymap().0.is_empty());
+        });
+    }
+}
@@ -0,0 +1,104 @@
+//! Capturing the next key combination the user presses, to rebind a hotkey.
+//!
+//! While recording, hotkeys are not dispatched. The combination ends with the first
+//! non-modifier input other than a left click, pressing [`KeyCode::Escape`] cancels the recording.
+
+use bevy::prelude::*;
+
+use super::{pressed_stroke, HotkeyRegistry};
+use crate::action::ActionId;
+
+#[derive(Resource, Default, Debug)]
+pub struct HotkeyRecorder {
+    target: Option<(ActionId, Option<usize>)>,
+}
+
+impl HotkeyRecorder {
+    /// Records into the `index`-th hotkey of `action`, or into a new hotkey if `index` is `None`.
+    pub fn start(&mut self, action: ActionId, index: Option<usize>) {
+        self.target = Some((action, index));
+    }
+    pub fn cancel(&mut self) {
+        self.target = None;
+    }
+    pub fn is_recording(&self) -> bool {
+        self.target.is_some()
+    }
+    pub fn target(&self) -> Option<(&ActionId, Option<usize>)> {
+        self.target.as_ref().map(|(id, index)| (id, *index))
+    }
+}
+
+pub(crate) fn record_hotkey(world: &mut World) {
+    let Some((id, index)) = world.resource::<HotkeyRecorder>().target.clone() else {
+        return;
+    };
+    let Some(keys) = pressed_stroke(world) else {
+        return;
+    };
+    let key = *keys.last().unwrap();
+    if key == MouseButton::Left.into() {
+        // clicks are for the user interface, like the button that started recording
+        return;
+    }
+    // the key should not trigger the binding it has just been recorded into
+    key.clear(world, false);
+    world.resource_mut::<HotkeyRecorder>().cancel();
+    if key == KeyCode::Escape.into() {
+        return;
+    }
+    world.resource_scope(|world, mut hotkeys: Mut<HotkeyRegistry>| {
+        hotkeys.set_keys(&id, index, keys, world);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hotkeys::{
+        shortcut::{format_binding, ShortcutStyle},
+        Hotkey, HotkeyPlugin, HotkeysExt,
+    };
+
+    #[test]
+    fn test_record_hotkey() {
+        let mut app = App::new();
+        app.add_plugins(HotkeyPlugin)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .register_hotkey("file.save", [Hotkey::new_global([KeyCode::F2])]);
+        let world = app.world_mut();
+        let id = ActionId::from("file.save");
+
+        world
+            .resource_mut::<HotkeyRecorder>()
+            .start(id.clone(), Some(0));
+        let mut input = world.resource_mut::<ButtonInput<KeyCode>>();
+        input.press(KeyCode::ControlLeft);
+        record_hotkey(world);
+        assert!(world.resource::<HotkeyRecorder>().is_recording());
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyS);
+        record_hotkey(world);
+        assert!(!world.resource::<HotkeyRecorder>().is_recording());
+        assert!(!world
+            .resource::<ButtonInput<KeyCode>>()
+            .just_pressed(KeyCode::KeyS));
+        let hotkeys = world.resource::<HotkeyRegistry>();
+        assert_eq!(
+            format_binding(&hotkeys[&id][0].binding, ShortcutStyle::Text),
+            "Ctrl+S"
+        );
+        assert!(hotkeys[&id][0].is_modified());
+
+        world
+            .resource_mut::<HotkeyRecorder>()
+            .start(id.clone(), None);
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Escape);
+        record_hotkey(world);
+        assert_eq!(world.resource::<HotkeyRegistry>()[&id].len(), 1);
+    }
+}
@@ -0,0 +1,221 @@
+//! Formatting bindings as shortcut text, like `Ctrl+Shift+P` or `⇧⌘P` on macOS.
+//!
+//! Key names are localized through the `key.*` entries of the locale files.
+//! Modifiers are sorted in the platform order whatever their order in the binding.
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use rust_i18n::t;
+
+use super::{InputKey, KeyBinding, Modifier, WheelDirection};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ShortcutStyle {
+    /// Names joined with `+`, like `Ctrl+Shift+P`.
+    Text,
+    /// Modifier and key symbols, like `⇧⌘P`.
+    Symbols,
+}
+
+impl ShortcutStyle {
+    /// [`ShortcutStyle::Symbols`] on macOS, [`ShortcutStyle::Text`] elsewhere.
+    pub fn platform() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::Symbols
+        } else {
+            Self::Text
+        }
+    }
+    fn modifier_order(self, modifier: Modifier) -> u8 {
+        match (self, modifier.resolve()) {
+            (Self::Text, Modifier::Ctrl) => 0,
+            (Self::Text, Modifier::Shift) => 1,
+            (Self::Text, Modifier::Alt) => 2,
+            (Self::Symbols, Modifier::Ctrl) => 0,
+            (Self::Symbols, Modifier::Alt) => 1,
+            (Self::Symbols, Modifier::Shift) => 2,
+            _ => 3,
+        }
+    }
+}
+
+fn owned(text: impl Into<String>) -> Cow<'static, str> {
+    Cow::Owned(text.into())
+}
+
+pub fn modifier_name(modifier: Modifier, style: ShortcutStyle) -> Cow<'static, str> {
+    let macos = cfg!(target_os = "macos");
+    match (style, modifier.resolve()) {
+        (ShortcutStyle::Symbols, Modifier::Ctrl) => "⌃".into(),
+        (ShortcutStyle::Symbols, Modifier::Alt) => "⌥".into(),
+        (ShortcutStyle::Symbols, Modifier::Shift) => "⇧".into(),
+        (ShortcutStyle::Symbols, _) => "⌘".into(),
+        (_, Modifier::Shift) => owned(t!("key.shift")),
+        (_, Modifier::Alt) if macos => owned(t!("key.option")),
+        (_, Modifier::Alt) => owned(t!("key.alt")),
+        (_, Modifier::Super) if macos => owned(t!("key.cmd")),
+        (_, Modifier::Super) if cfg!(target_os = "windows") => owned(t!("key.win")),
+        (_, Modifier::Super) => owned(t!("key.super")),
+        (_, _) => owned(t!("key.ctrl")),
+    }
+}
+
+fn key_symbol(code: KeyCode) -> Option<&'static str> {
+    use KeyCode::*;
+    Some(match code {
+        Enter => "↩",
+        Backspace => "⌫",
+        Delete => "⌦",
+        Escape => "⎋",
+        Tab => "⇥",
+        PageUp => "⇞",
+        PageDown => "⇟",
+        Home => "↖",
+        End => "↘",
+        ArrowUp => "↑",
+        ArrowDown => "↓",
+        ArrowLeft => "←",
+        ArrowRight => "→",
+        _ => return None,
+    })
+}
+
+/// The name of a key as printed on the keyboard, with the modifier names of the current platform.
+pub fn key_name(code: KeyCode) -> Cow<'static, str> {
+    use KeyCode::*;
+    if let Some(modifier) = Modifier::from_key(code) {
+        return modifier_name(modifier, ShortcutStyle::Text);
+    }
+    let name = match code {
+        Escape => t!("key.esc"),
+        Enter => t!("key.enter"),
+        Space => t!("key.space"),
+        Tab => t!("key.tab"),
+        Backspace => t!("key.backspace"),
+        Delete => t!("key.delete"),
+        Insert => t!("key.insert"),
+        Home => t!("key.home"),
+        End => t!("key.end"),
+        PageUp => t!("key.page_up"),
+        PageDown => t!("key.page_down"),
+        ArrowUp => t!("key.up"),
+        ArrowDown => t!("key.down"),
+        ArrowLeft => t!("key.left"),
+        ArrowRight => t!("key.right"),
+        Backquote => "`".into(),
+        Minus => "-".into(),
+        Equal => "=".into(),
+        BracketLeft => "[".into(),
+        BracketRight => "]".into(),
+        Backslash => "\\".into(),
+        Semicolon => ";".into(),
+        Quote => "'".into(),
+        Comma => ",".into(),
+        Period => ".".into(),
+        Slash => "/".into(),
+        NumpadAdd => "Num+".into(),
+        NumpadSubtract => "Num-".into(),
+        NumpadMultiply => "Num*".into(),
+        NumpadDivide => "Num/".into(),
+        NumpadEnter => "NumEnter".into(),
+        _ => {
+            let debug = format!("{code:?}");
+            return match debug
+                .strip_prefix("Key")
+                .or_else(|| debug.strip_prefix("Digit"))
+            {
+                Some(name) => owned(name),
+                None => owned(debug),
+            };
+        }
+    };
+    owned(name)
+}
+
+pub fn input_name(key: InputKey, style: ShortcutStyle) -> Cow<'static, str> {
+    match key {
+        InputKey::Modifier(modifier) => modifier_name(modifier, style),
+        InputKey::Keyboard(code) => match (style, key_symbol(code)) {
+            (ShortcutStyle::Symbols, Some(symbol)) => symbol.into(),
+            _ => key_name(code),
+        },
+        InputKey::Mouse(button) => owned(match button {
+            MouseButton::Left => t!("key.mouse_left"),
+            MouseButton::Right => t!("key.mouse_right"),
+            MouseButton::Middle => t!("key.mouse_middle"),
+            MouseButton::Back => t!("key.mouse_back"),
+            MouseButton::Forward => t!("key.mouse_forward"),
+            MouseButton::Other(n) => t!("key.mouse_other", n = n),
+        }),
+        InputKey::Wheel(direction) => owned(match direction {
+            WheelDirection::Up => t!("key.scroll_up"),
+            WheelDirection::Down => t!("key.scroll_down"),
+            WheelDirection::Left => t!("key.scroll_left"),
+            WheelDirection::Right => t!("key.scroll_right"),
+        }),
+        InputKey::Gamepad(GamepadButtonType::Other(n)) => owned(t!("key.gamepad", button = n)),
+        InputKey::Gamepad(ty) => owned(t!("key.gamepad", button = format!("{ty:?}"))),
+    }
+}
+
+/// Formats keys held together, modifiers first.
+pub fn format_stroke(stroke: &[InputKey], style: ShortcutStyle) -> String {
+    let mut modifiers: Vec<Modifier> = stroke.iter().filter_map(|key| key.modifier()).collect();
+    modifiers.sort_by_key(|modifier| style.modifier_order(*modifier));
+    modifiers.dedup();
+    let modifiers = modifiers
+        .into_iter()
+        .map(|modifier| modifier_name(modifier, style));
+    let keys = stroke
+        .iter()
+        .filter(|key| !key.is_modifier())
+        .map(|key| input_name(*key, style));
+    match style {
+        ShortcutStyle::Text => modifiers.chain(keys).collect::<Vec<_>>().join("+"),
+        ShortcutStyle::Symbols => {
+            modifiers.collect::<String>() + &keys.collect::<Vec<_>>().join("+")
+        }
+    }
+}
+
+/// Formats every stroke of `binding`, separated by commas for chords.
+pub fn format_binding(binding: &KeyBinding, style: ShortcutStyle) -> String {
+    binding
+        .strokes()
+        .map(|stroke| format_stroke(stroke, style))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hotkeys::TriggerType;
+
+    #[test]
+    fn test_format_binding() {
+        let palette = KeyBinding::new(
+            [
+                InputKey::from(KeyCode::KeyP),
+                KeyCode::ShiftRight.into(),
+                Modifier::Super.into(),
+            ],
+            TriggerType::Pressed,
+        );
+        assert_eq!(format_binding(&palette, ShortcutStyle::Symbols), "⇧⌘P");
+        let chord = KeyBinding::chord([
+            [InputKey::from(KeyCode::AltLeft), KeyCode::ArrowUp.into()],
+            [Modifier::Ctrl.into(), KeyCode::KeyK.into()],
+        ]);
+        assert_eq!(format_binding(&chord, ShortcutStyle::Symbols), "⌥↑, ⌃K");
+        let middle = KeyBinding::new(
+            [InputKey::from(Modifier::Shift), MouseButton::Middle.into()],
+            TriggerType::Pressed,
+        );
+        assert_eq!(
+            format_binding(&middle, ShortcutStyle::Text),
+            "Shift+Middle Mouse"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Saving and restoring the dock layout ([`HeDockState`]) to disk.
+//!
+//! The layout is restored at startup and saved on exit to the file in [`LayoutConfig`].
+//! Tabs that are no longer registered in [`TabRegistry`] are dropped when loading.
+//! Tabs torn off into their own window are saved as floating windows of the layout.
+//! Layouts are stored as RON, JSON cannot represent the infinite rects egui_dock uses.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use egui_dock::DockState;
+use snafu::{ResultExt, Snafu};
+
+use crate::{
+    action::ActionsExt,
+    tab_system::{areas::DockAreas, HeDockState, TabId, TabRegistry},
+};
+
+pub const SAVE_LAYOUT: &str = "layout.save";
+pub const LOAD_LAYOUT: &str = "layout.load";
+pub const RESET_LAYOUT: &str = "layout.reset";
+
+#[derive(Resource, Clone, Debug)]
+pub struct LayoutConfig {
+    pub path: PathBuf,
+    pub load_on_startup: bool,
+    pub save_on_exit: bool,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            path: "layout.ron".into(),
+            load_on_startup: true,
+            save_on_exit: true,
+        }
+    }
+}
+
+/// The layout before loading from disk, used by [`RESET_LAYOUT`].
+#[derive(Resource, Clone, Debug)]
+pub struct DefaultLayout(pub DockState<TabId>);
+
+#[derive(Snafu, Debug)]
+pub enum LayoutError {
+    #[snafu(display("cannot access layout file {}: {source}", path.display()))]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("cannot serialize layout: {source}"))]
+    SerializeRon { source: ron::Error },
+    #[snafu(display("cannot parse layout: {source}"))]
+    DeserializeRon { source: ron::error::SpannedError },
+    #[snafu(display("the layout contains no registered tab"))]
+    Empty,
+}
+
+pub fn save_layout(state: &DockState<TabId>, path: &Path) -> Result<(), LayoutError> {
+    let text = ron::ser::to_string_pretty(state, Default::default()).context(SerializeRonSnafu)?;
+    fs::write(path, text).context(IoSnafu { path })
+}
+
+/// Reads a layout, dropping tabs missing from `registry`.
+pub fn load_layout(path: &Path, registry: &TabRegistry) -> Result<DockState<TabId>, LayoutError> {
+    let text = fs::read_to_string(path).context(IoSnafu { path })?;
+    let mut state: DockState<TabId> = ron::from_str(&text).context(DeserializeRonSnafu)?;
+    state.retain_tabs(|tab| registry.contains_key(tab));
+    if state.iter_all_tabs().next().is_none() {
+        return Err(LayoutError::Empty);
+    }
+    Ok(state)
+}
+
+/// The main dock with the tabs of the windows in `areas`, which are not part of it.
+fn layout_to_save(state: &HeDockState, areas: Option<&DockAreas>) -> DockState<TabId> {
+    match areas {
+        Some(areas) => areas.with_window_tabs(&state.0),
+        None => state.0.clone(),
+    }
+}
+
+fn restore_layout(
+    mut commands: Commands,
+    state: Option<ResMut<HeDockState>>,
+    config: Res<LayoutConfig>,
+    registry: Res<TabRegistry>,
+) {
+    let Some(mut state) = state else {
+        return;
+    };
+    commands.insert_resource(DefaultLayout(state.0.clone()));
+    if !config.load_on_startup || !config.path.exists() {
+        return;
+    }
+    match load_layout(&config.path, &registry) {
+        Ok(loaded) => state.0 = loaded,
+        Err(err) => warn!("layout not restored: {err}"),
+    }
+}
+
+fn save_layout_on_exit(
+    mut exit: EventReader<AppExit>,
+    state: Option<Res<HeDockState>>,
+    areas: Option<Res<DockAreas>>,
+    config: Res<LayoutConfig>,
+) {
+    if exit.read().next().is_none() || !config.save_on_exit {
+        return;
+    }
+    if let Some(state) = state {
+        let layout = layout_to_save(&state, areas.as_deref());
+        if let Err(err) = save_layout(&layout, &config.path) {
+            error!("layout not saved: {err}");
+        }
+    }
+}
+
+fn save_layout_action(
+    state: Option<Res<HeDockState>>,
+    areas: Option<Res<DockAreas>>,
+    config: Res<LayoutConfig>,
+) -> Result<(), String> {
+    let state = state.ok_or("no dock layout to save")?;
+    let layout = layout_to_save(&state, areas.as_deref());
+    save_layout(&layout, &config.path).map_err(|err| err.to_string())
+}
+
+fn load_layout_action(
+    mut commands: Commands,
+    state: Option<ResMut<HeDockState>>,
+    areas: Option<ResMut<DockAreas>>,
+    config: Res<LayoutConfig>,
+    registry: Res<TabRegistry>,
+) -> Result<(), String> {
+    let mut state = state.ok_or("no dock layout to load into")?;
+    state.0 = load_layout(&config.path, &registry).map_err(|err| err.to_string())?;
+    // The loaded layout has its own floating windows, the tabs of the open ones would be shown twice.
+    if let Some(mut areas) = areas {
+        for window in areas.remove_windows() {
+            commands.entity(window).despawn();
+        }
+    }
+    Ok(())
+}
+
+fn reset_layout_action(state: Option<ResMut<HeDockState>>, default: Option<Res<DefaultLayout>>) {
+    if let (Some(mut state), Some(default)) = (state, default) {
+        state.0 = default.0.clone();
+    }
+}
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LayoutConfig>()
+            .register_action(SAVE_LAYOUT, "Save layout", save_layout_action)
+            .register_action(LOAD_LAYOUT, "Load layout", load_layout_action)
+            .register_action(RESET_LAYOUT, "Reset layout", reset_layout_action)
+            .add_systems(Startup, restore_layout)
+            .add_systems(Last, save_layout_on_exit);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use egui::Ui;
+
+    use super::*;
+    use crate::tab_system::{TabPlugin, TabRegistrationExt};
+
+    fn empty_tab(In(_): In<Ui>) {}
+
+    /// A file in the temp dir unique to the test and the process, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("helium_{name}_{}.ron", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_layout_roundtrip() {
+        let mut app = App::new();
+        app.add_plugins(TabPlugin)
+            .register_tab("kept", "Kept", empty_tab, || true);
+        let registry = app.world().resource::<TabRegistry>();
+        let mut state = DockState::new(vec![TabId::from("kept")]);
+        state.add_window(vec!["removed".into()]);
+
+        let file = TempFile::new("layout_roundtrip");
+        save_layout(&state, &file.0).unwrap();
+        let loaded = load_layout(&file.0, registry).unwrap();
+        let tabs: Vec<_> = loaded.iter_all_tabs().map(|(_, tab)| tab.clone()).collect();
+        assert_eq!(tabs, vec![TabId::from("kept")]);
+    }
+}
@@ -1,28 +1,41 @@
 pub mod action;
 pub mod hotkeys;
+#[cfg(feature = "serde")]
+pub mod layout;
 pub mod menu;
+pub mod shell;
 pub mod tab_system;
 pub mod utils;
 pub mod widgets;
+pub mod workspace;
 pub mod notifications;
+pub mod palette;
+pub mod undo;
 
 use action::ActionPlugin;
 use bevy::app::Plugin;
 use hotkeys::HotkeyPlugin;
 use menu::MenuPlugin;
 use notifications::NotificationPlugin;
+use palette::CommandPalettePlugin;
 use rust_i18n::i18n;
 use tab_system::TabPlugin;
-i18n!();
+use undo::UndoPlugin;
+use workspace::WorkspacePlugin;
+i18n!("locales", fallback = "en");
 
 pub struct HeliumFramework;
 
 impl Plugin for HeliumFramework {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_plugins((ActionPlugin, HotkeyPlugin, TabPlugin, MenuPlugin, NotificationPlugin));
+        app.add_plugins((ActionPlugin, HotkeyPlugin, TabPlugin, MenuPlugin, NotificationPlugin, CommandPalettePlugin, UndoPlugin, WorkspacePlugin));
+        #[cfg(feature = "serde")]
+        app.add_plugins((layout::LayoutPlugin, hotkeys::keymap::KeymapPlugin));
     }
 }
 
 pub mod prelude {
-    pub use super::{action::*, hotkeys::*, menu::*, tab_system::*, utils::*, notifications::*, HeliumFramework};
+    pub use super::{action::*, hotkeys::*, menu::*, tab_system::*, utils::*, notifications::*, palette::*, shell::*, undo::*, workspace::*, HeliumFramework};
+    #[cfg(feature = "serde")]
+    pub use super::layout::*;
 }
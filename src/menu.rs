@@ -1,7 +1,7 @@
 use std::{borrow::Cow, fmt::Debug};
 
 use bevy::{
-    app::{App, Plugin}, ecs::schedule::{BoxedCondition, Condition}, prelude::{Deref, DerefMut, Mut, Resource, World}
+    app::{App, Plugin}, ecs::schedule::{BoxedCondition, Condition}, prelude::{Deref, DerefMut, Mut, Reflect, Resource, World}
 };
 use egui::Ui;
 use enum_dispatch::enum_dispatch;
@@ -9,20 +9,36 @@ use indexmap::IndexMap;
 use snafu::Snafu;
 
 use crate::{
+    hotkeys::HotkeyRegistry,
+    notifications::notify_action_result,
     prelude::{ActionId, ActionRegistry},
-    utils::new_condition,
+    utils::{identifier::Identifier, new_condition},
 };
 
+mod checkable;
+pub mod context;
+mod dynamic;
+pub mod navigation;
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod definition;
+
+pub use checkable::*;
+pub use dynamic::*;
+
+/// Shows [`EditorMenuEntrys`] as a menu bar, see [`navigation`] for the keyboard controls.
 pub fn show_menu_ui(ui: &mut Ui, world: &mut World) {
-    world.resource_scope(|world: &mut World, mut entry:Mut<EditorMenuEntrys>| {
-        entry.0.foreach_ui(ui, world);
-    });
+    navigation::menu_bar_ui(ui, world);
 }
 
 #[enum_dispatch(MenuItemProvider)]
 #[derive(Debug)]
 pub enum MenuItemVariant {
     Button,
+    Checkbox,
+    RadioGroup,
+    Toggle,
+    Dynamic,
     Custom,
     SubMenu,
     Category,
@@ -33,6 +49,8 @@ pub struct MenuItem {
     pub name: Cow<'static, str>,
     pub source: MenuItemVariant,
     pub piority: usize,
+    /// Hidden items stay in the menu tree but are not shown.
+    pub hidden: bool,
 }
 
 #[enum_dispatch]
@@ -53,7 +71,7 @@ pub trait MenuItemProvider {
             Some(item)
         }
     }
-    fn as_container(&mut self) -> Option<ItemAsContainer> {
+    fn as_container(&mut self) -> Option<ItemAsContainer<'_>> {
         None
     }
 }
@@ -63,6 +81,11 @@ pub trait ContainerItem<'item> {
     fn remove_item(&mut self, id: &str) -> Option<MenuItem>;
     fn get_item(&self, id: &str) -> Option<&MenuItem>;
     fn get_item_mut(&mut self, id: &str) -> Option<&mut MenuItem>;
+    /// Inserts at `index` among all the items of the container, moving the item if `id` exists.
+    /// The items are then sorted by priority, which is stable, so `index` only orders the item
+    /// relative to those of the same priority.
+    fn insert_item(&mut self, index: usize, id: &str, menu_item: MenuItem);
+    fn index_of(&self, id: &str) -> Option<usize>;
 }
 
 pub struct ItemAsContainer<'item> {
@@ -81,24 +104,58 @@ impl ItemAsContainer<'_> {
     pub fn get_item_mut(&mut self, id: &str) -> Option<&mut MenuItem> {
         self.container_item.get_item_mut(id)
     }
+    pub fn insert_item(&mut self, index: usize, id: &str, menu_item: MenuItem) {
+        self.container_item.insert_item(index, id, menu_item)
+    }
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.container_item.index_of(id)
+    }
+    pub fn is_container(&self, id: &str) -> bool {
+        self.get_item(id).is_some_and(|item| {
+            matches!(
+                item.source,
+                MenuItemVariant::SubMenu(_) | MenuItemVariant::Category(_)
+            )
+        })
+    }
 }
 
+/// Runs an action when clicked. The first hotkey bound to the action is shown as shortcut text.
 pub struct Button {
     action: ActionId,
     avalible: BoxedCondition,
+    icon: Option<egui::ImageSource<'static>>,
+    tooltip: bool,
 }
 
 impl Button {
     pub fn new(action: impl Into<ActionId>) -> Self {
-        Self {
-            action: action.into(),
-            avalible: new_condition(|| true),
-        }
+        Self::new_conditioned(action, || true)
     }
     pub fn new_conditioned<M>(action: impl Into<ActionId>, available: impl Condition<M>) -> Self {
         Self {
             action: action.into(),
             avalible: new_condition(available),
+            icon: None,
+            tooltip: false,
+        }
+    }
+    pub fn with_icon(mut self, icon: impl Into<egui::ImageSource<'static>>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+    /// Shows the description of the action when hovered.
+    pub fn with_tooltip(mut self) -> Self {
+        self.tooltip = true;
+        self
+    }
+    pub fn is_available(&mut self, world: &mut World) -> bool {
+        self.avalible.run_readonly((), world)
+    }
+    /// Runs the action if available, as if clicked.
+    pub fn activate(&mut self, world: &mut World) {
+        if self.is_available(world) {
+            run_menu_action(&self.action, world);
         }
     }
 }
@@ -111,13 +168,28 @@ impl Debug for Button {
 
 impl MenuItemProvider for Button {
     fn ui(&mut self, ui: &mut Ui, world: &mut World, name: &str) {
+        let shortcut = world
+            .get_resource::<HotkeyRegistry>()
+            .and_then(|hotkeys| hotkeys.shortcut_text(&self.action))
+            .unwrap_or_default();
+        let description = self
+            .tooltip
+            .then(|| world.resource::<ActionRegistry>().get(&self.action))
+            .flatten()
+            .map(|action| action.get_description().to_owned());
         ui.add_enabled_ui(self.avalible.run_readonly((), world), |ui| {
-            if ui.button(name).clicked() {
-                world.resource_scope(|world: &mut World, mut actions: Mut<ActionRegistry>| {
-                    let _ = actions.run_instant(&self.action, (), world).map_err(|err| {
-                        bevy::prelude::error!("encountered error when running action: {}", err)
-                    });
-                });
+            let button = match &self.icon {
+                Some(icon) => egui::Button::image_and_text(icon.clone(), name),
+                None => egui::Button::new(name),
+            };
+            let mut response = ui.add(button.shortcut_text(shortcut));
+            if let Some(description) = description {
+                response = response
+                    .on_hover_text(&description)
+                    .on_disabled_hover_text(description);
+            }
+            if response.clicked() {
+                run_menu_action(&self.action, world);
                 ui.close_menu();
             }
         });
@@ -127,6 +199,32 @@ impl MenuItemProvider for Button {
     }
 }
 
+/// Runs `action` from a menu. Inside a context menu it is queued with the target as input.
+pub fn run_menu_action(action: &ActionId, world: &mut World) {
+    if context::queue_in_context(action, world) {
+        return;
+    }
+    world.resource_scope(|world: &mut World, mut actions: Mut<ActionRegistry>| {
+        let result = actions.run_instant(action, (), world);
+        notify_action_result(world, action, &result);
+    });
+}
+
+/// Same as [`run_menu_action`] with an explicit input, also used inside context menus.
+pub fn run_menu_action_with(action: &ActionId, input: Box<dyn Reflect>, world: &mut World) {
+    if context::in_context_menu(world) {
+        world.send_event(context::ContextAction {
+            action: action.clone(),
+            input,
+        });
+        return;
+    }
+    world.resource_scope(|world: &mut World, mut actions: Mut<ActionRegistry>| {
+        let result = actions.run_instant_dyn(action, input, world);
+        notify_action_result(world, action, &result);
+    });
+}
+
 pub trait UiFunc: Fn(&mut Ui, &mut World, &str) + Sync + Send + 'static {}
 
 impl<T> UiFunc for T where T: Fn(&mut Ui, &mut World, &str) + Sync + Send + 'static {}
@@ -155,15 +253,29 @@ impl ItemGroup {
         self.items.values_mut()
     }
     pub fn foreach_ui(&mut self, ui: &mut Ui, world: &mut World) {
-        for item in self.iter_items_mut() {
-            item.source.ui(ui, world, &item.name);
+        for item in self.iter_items_mut().filter(|item| !item.hidden) {
+            item.source.ui(ui, world, &navigation::menu_label(&item.name));
         }
     }
-    pub fn as_container(&mut self) -> ItemAsContainer {
+    pub fn as_container(&mut self) -> ItemAsContainer<'_> {
         ItemAsContainer {
             container_item: Box::new(ItemGroupAsContainer { group: self }),
         }
     }
+    /// Same as [`MenuItemProvider::find_subitem_recursive`], starting from this group.
+    pub fn find_item_recursive(&mut self, id: &str) -> Option<&mut MenuItem> {
+        let (id, trailing) = id.split_once('.').map_or((id, None), |(id, rest)| (id, Some(rest)));
+        let item = self.items.get_mut(id)?;
+        match trailing {
+            Some(trailing) => item.source.find_subitem_recursive(trailing),
+            None => Some(item),
+        }
+    }
+    /// Stable, so items of the same priority keep the order they were inserted in.
+    fn sort(&mut self) {
+        self.items
+            .sort_by(|_, item1, _, item2| item1.piority.cmp(&item2.piority));
+    }
 }
 
 struct ItemGroupAsContainer<'group> {
@@ -173,9 +285,21 @@ struct ItemGroupAsContainer<'group> {
 impl<'item> ContainerItem<'item> for ItemGroupAsContainer<'item> {
     fn add_item(&mut self, id: &str, menu_item: MenuItem) {
         self.group.items.insert(id.to_string(), menu_item);
-        self.group
-            .items
-            .sort_unstable_by(|_, item1, _, item2| item1.piority.cmp(&item2.piority));
+        self.group.sort();
+    }
+    fn insert_item(&mut self, index: usize, id: &str, menu_item: MenuItem) {
+        // Moving an existing item only accepts the indices it can end up at.
+        let len = self.group.items.len();
+        let index = if self.group.items.contains_key(id) {
+            index.min(len - 1)
+        } else {
+            index.min(len)
+        };
+        self.group.items.shift_insert(index, id.to_string(), menu_item);
+        self.group.sort();
+    }
+    fn index_of(&self, id: &str) -> Option<usize> {
+        self.group.items.get_index_of(id)
     }
     fn get_item_mut(&mut self, id: &str) -> Option<&mut MenuItem> {
         self.group.items.get_mut(id)
@@ -218,7 +342,7 @@ impl MenuItemProvider for Category {
     fn find_subitem_mut(&mut self, sub_id: &str) -> Option<&mut MenuItem> {
         self.group.items.get_mut(sub_id)
     }
-    fn as_container(&mut self) -> Option<ItemAsContainer> {
+    fn as_container(&mut self) -> Option<ItemAsContainer<'_>> {
         Some(self.group.as_container())
     }
 }
@@ -226,7 +350,15 @@ pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<EditorMenuEntrys>();
+        app.init_resource::<EditorMenuEntrys>()
+            .init_resource::<navigation::MenuNavigation>()
+            .init_resource::<search::MenuSearch>();
+        // The context menus register their actions, whether or not `ActionPlugin` was added first.
+        app.init_resource::<ActionRegistry>();
+        context::build_context_menus(app);
+        #[cfg(feature = "serde")]
+        app.init_resource::<definition::MenuFiles>()
+            .add_systems(bevy::app::Startup, definition::load_menu_files);
     }
 }
 
@@ -261,6 +393,7 @@ impl MenuContext<'_> {
         });
         Ok(())
     }
+    /// Merges into the submenu or category `id` if it exists, keeping its name and priority.
     pub fn with_category(
         &mut self,
         id: &str,
@@ -268,9 +401,12 @@ impl MenuContext<'_> {
         piority: usize,
         add_sub: impl FnOnce(MenuContext),
     ) {
-        self.add(id, name, Category::default(), piority);
+        if !self.item.is_container(id) {
+            self.add(id, name, Category::default(), piority);
+        }
         self.inside_sub(id, add_sub).unwrap();
     }
+    /// Merges into the submenu or category `id` if it exists, keeping its name and priority.
     pub fn with_sub_menu(
         &mut self,
         id: &str,
@@ -278,9 +414,105 @@ impl MenuContext<'_> {
         piority: usize,
         add_sub: impl FnOnce(MenuContext),
     ) {
-        self.add(id, name, SubMenu::default(), piority);
+        if !self.item.is_container(id) {
+            self.add(id, name, SubMenu::default(), piority);
+        }
         self.inside_sub(id, add_sub).unwrap();
     }
+    /// Runs `add_sub` inside the container at the dotted `path`,
+    /// missing submenus are created with their id as name.
+    pub fn sub_menu_at<'a>(
+        &mut self,
+        path: &'a Identifier,
+        add_sub: impl FnOnce(MenuContext),
+    ) -> Result<(), MenuError<'a>> {
+        self.sub_menu_segments(path, add_sub)
+    }
+    fn sub_menu_segments<'a>(
+        &mut self,
+        path: &'a [String],
+        add_sub: impl FnOnce(MenuContext),
+    ) -> Result<(), MenuError<'a>> {
+        let Some((id, rest)) = path.split_first() else {
+            return Ok(());
+        };
+        if self.item.get_item(id).is_none() {
+            self.add(id, id.clone().into(), SubMenu::default(), 0);
+        }
+        if rest.is_empty() {
+            return self.inside_sub(id, add_sub);
+        }
+        let mut result = Ok(());
+        self.inside_sub(id, |mut sub| result = sub.sub_menu_segments(rest, add_sub))?;
+        result
+    }
+    /// Adds the item right before `sibling`, with the same priority.
+    pub fn add_before<'a>(
+        &mut self,
+        sibling: &'a str,
+        id: &str,
+        name: Cow<'static, str>,
+        item: impl Into<MenuItemVariant>,
+    ) -> Result<(), MenuError<'a>> {
+        self.add_next_to(sibling, 0, id, name, item)
+    }
+    /// Adds the item right after `sibling`, with the same priority.
+    pub fn add_after<'a>(
+        &mut self,
+        sibling: &'a str,
+        id: &str,
+        name: Cow<'static, str>,
+        item: impl Into<MenuItemVariant>,
+    ) -> Result<(), MenuError<'a>> {
+        self.add_next_to(sibling, 1, id, name, item)
+    }
+    fn add_next_to<'a>(
+        &mut self,
+        sibling: &'a str,
+        offset: usize,
+        id: &str,
+        name: Cow<'static, str>,
+        item: impl Into<MenuItemVariant>,
+    ) -> Result<(), MenuError<'a>> {
+        if self.item.index_of(sibling).is_none() {
+            return Err(MenuError::NotFound { id: sibling });
+        }
+        // Adding an item next to itself replaces it where it is.
+        let offset = if sibling == id {
+            0
+        } else {
+            self.item.remove_item(id);
+            offset
+        };
+        let index = self
+            .item
+            .index_of(sibling)
+            .ok_or(MenuError::NotFound { id: sibling })?;
+        let piority = self.item.get_item(sibling).map_or(0, |item| item.piority);
+        let mut source = item.into();
+        source.initialize(self.world);
+        self.item.insert_item(
+            index + offset,
+            id,
+            MenuItem {
+                name,
+                source,
+                piority,
+                hidden: false,
+            },
+        );
+        Ok(())
+    }
+    pub fn remove(&mut self, id: &str) -> Option<MenuItem> {
+        self.item.remove_item(id)
+    }
+    pub fn set_hidden<'a>(&mut self, id: &'a str, hidden: bool) -> Result<(), MenuError<'a>> {
+        self.item
+            .get_item_mut(id)
+            .ok_or(MenuError::NotFound { id })?
+            .hidden = hidden;
+        Ok(())
+    }
     pub fn add(
         &mut self,
         id: &str,
@@ -296,6 +528,7 @@ impl MenuContext<'_> {
                 name,
                 source,
                 piority,
+                hidden: false,
             },
         );
     }
@@ -327,6 +560,15 @@ pub enum MenuError<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::action::ActionPlugin;
+
+    #[test]
+    fn test_plugin_order() {
+        let mut app = App::new();
+        app.add_plugins((MenuPlugin, ActionPlugin));
+        let actions = app.world().resource::<ActionRegistry>();
+        assert!(actions.contains_key(&ActionId::from(context::CLOSE_TAB)));
+    }
 
     #[test]
     fn test_find_item() {
@@ -338,6 +580,57 @@ mod test {
             .find_subitem_recursive("category2.item1.nonexist"));
     }
 
+    #[test]
+    fn test_merge_and_order() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, MenuPlugin));
+        let ids = |group: &ItemGroup| group.items.keys().cloned().collect::<Vec<_>>();
+        app.menu_context(|mut ctx| {
+            ctx.with_sub_menu("file", "File".into(), 0, |mut ctx| {
+                ctx.add("open", "Open".into(), Button::new("file.open"), 0);
+                ctx.add("quit", "Quit".into(), Button::new("file.quit"), 9);
+            });
+        })
+        .menu_context(|mut ctx| {
+            ctx.with_sub_menu("file", "Plugin file".into(), 5, |mut ctx| {
+                ctx.add("save", "Save".into(), Button::new("file.save"), 0);
+                ctx.add_before("save", "new", "New".into(), Button::new("file.new"))
+                    .unwrap();
+                ctx.add_after("new", "new", "New…".into(), Button::new("file.new"))
+                    .unwrap();
+                ctx.add_after("quit", "quit", "Quit".into(), Button::new("file.quit"))
+                    .unwrap();
+                ctx.set_hidden("quit", true).unwrap();
+                assert!(ctx.add_after("missing", "x", "X".into(), Button::new("x")).is_err());
+                // An existing item is kept when the sibling does not exist.
+                assert!(ctx
+                    .add_after("missing", "save", "Save".into(), Button::new("x"))
+                    .is_err());
+            });
+            ctx.sub_menu_at(&"edit.advanced".into(), |mut ctx| {
+                ctx.add("format", "Format".into(), Button::new("edit.format"), 0);
+            })
+            .unwrap();
+        });
+
+        let mut entrys = app.world_mut().resource_mut::<EditorMenuEntrys>();
+        assert_eq!(ids(&entrys), ["file", "edit"]);
+        let file = entrys.items.get_mut("file").unwrap();
+        assert_eq!((file.name.as_ref(), file.piority), ("File", 0));
+        let MenuItemVariant::SubMenu(file) = &file.source else {
+            panic!("file is not a submenu");
+        };
+        assert_eq!(ids(&file.group), ["open", "new", "save", "quit"]);
+        assert_eq!(file.group.items["new"].name, "New…");
+        assert!(file.group.items["quit"].hidden);
+        let edit = entrys.items.get_mut("edit").unwrap();
+        assert_eq!(edit.name, "edit");
+        assert!(edit
+            .source
+            .find_subitem_recursive("advanced.format")
+            .is_some());
+    }
+
     fn construct_menu() -> MenuItem {
         let category = MenuItem {
             name: "category".into(),
@@ -353,6 +646,7 @@ mod test {
             }
             .into(),
             piority: 0,
+            hidden: false,
         };
 
         MenuItem {
@@ -369,6 +663,7 @@ mod test {
             }
             .into(),
             piority: 1,
+            hidden: false,
         }
     }
 
@@ -377,6 +672,7 @@ mod test {
             name,
             source: Button::new("wtf.is.this").into(),
             piority: 0,
+            hidden: false,
         }
     }
 }
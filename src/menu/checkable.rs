@@ -0,0 +1,220 @@
+//! Menu items showing a state: [`Checkbox`] and [`RadioGroup`] edit a resource,
+//! [`Toggle`] runs an action and shows a condition.
+
+use std::fmt::Debug;
+
+use bevy::{
+    ecs::schedule::{BoxedCondition, Condition},
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, ReflectRef, TypeInfo, Typed, VariantInfo},
+};
+use egui::Ui;
+
+use super::{run_menu_action, MenuItemProvider};
+use crate::{action::ActionId, utils::new_condition};
+
+type Getter<T> = Box<dyn Fn(&mut World) -> T + Send + Sync>;
+type Setter<T> = Box<dyn Fn(&mut World, T) + Send + Sync>;
+
+/// A checkbox editing a `bool`, usually a field of a resource, see [`Checkbox::from_resource`].
+pub struct Checkbox {
+    get: Getter<bool>,
+    set: Setter<bool>,
+}
+
+impl Checkbox {
+    pub fn new(
+        get: impl Fn(&mut World) -> bool + Send + Sync + 'static,
+        set: impl Fn(&mut World, bool) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            get: Box::new(get),
+            set: Box::new(set),
+        }
+    }
+    /// Edits the field returned by `field`, unchecked while the resource does not exist.
+    pub fn from_resource<R: Resource>(field: fn(&mut R) -> &mut bool) -> Self {
+        Self::new(
+            move |world| {
+                world
+                    .get_resource_mut::<R>()
+                    .is_some_and(|mut resource| *field(resource.bypass_change_detection()))
+            },
+            move |world, checked| {
+                if let Some(mut resource) = world.get_resource_mut::<R>() {
+                    *field(&mut resource) = checked;
+                }
+            },
+        )
+    }
+    pub fn is_checked(&self, world: &mut World) -> bool {
+        (self.get)(world)
+    }
+    pub fn set(&self, world: &mut World, checked: bool) {
+        (self.set)(world, checked)
+    }
+}
+
+impl Debug for Checkbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<Checkbox>")
+    }
+}
+
+impl MenuItemProvider for Checkbox {
+    fn ui(&mut self, ui: &mut Ui, world: &mut World, name: &str) {
+        let mut checked = self.is_checked(world);
+        if ui.checkbox(&mut checked, name).clicked() {
+            self.set(world, checked);
+        }
+    }
+}
+
+/// One radio button per unit variant of an enum resource, selecting one sets the resource through reflection.
+pub struct RadioGroup {
+    variants: Vec<&'static str>,
+    get: Getter<Option<String>>,
+    set: Setter<&'static str>,
+}
+
+impl RadioGroup {
+    /// # Panics
+    /// If `R` is not an enum.
+    pub fn from_resource<R: Resource + Reflect + Typed>() -> Self {
+        let TypeInfo::Enum(info) = R::type_info() else {
+            panic!("{} is not an enum", R::type_info().type_path());
+        };
+        Self {
+            variants: info
+                .iter()
+                .filter(|variant| matches!(variant, VariantInfo::Unit(_)))
+                .map(VariantInfo::name)
+                .collect(),
+            get: Box::new(|world| {
+                let resource = world.get_resource::<R>()?;
+                match resource.reflect_ref() {
+                    ReflectRef::Enum(value) => Some(value.variant_name().to_owned()),
+                    _ => None,
+                }
+            }),
+            set: Box::new(|world, variant| {
+                if let Some(mut resource) = world.get_resource_mut::<R>() {
+                    resource.apply(&DynamicEnum::new(variant.to_owned(), DynamicVariant::Unit));
+                }
+            }),
+        }
+    }
+    pub fn variants(&self) -> &[&'static str] {
+        &self.variants
+    }
+    pub fn selected(&self, world: &mut World) -> Option<String> {
+        (self.get)(world)
+    }
+    /// Does nothing if `variant` is not a unit variant of the enum.
+    pub fn select(&self, world: &mut World, variant: &str) {
+        if let Some(variant) = self.variants.iter().find(|name| **name == variant) {
+            (self.set)(world, variant)
+        }
+    }
+}
+
+impl Debug for RadioGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RadioGroup")
+            .field("variants", &self.variants)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MenuItemProvider for RadioGroup {
+    fn ui(&mut self, ui: &mut Ui, world: &mut World, name: &str) {
+        if !name.is_empty() {
+            ui.label(name);
+        }
+        let selected = self.selected(world);
+        for variant in &self.variants {
+            if ui
+                .radio(selected.as_deref() == Some(*variant), *variant)
+                .clicked()
+            {
+                (self.set)(world, variant);
+            }
+        }
+    }
+}
+
+/// Runs an action when clicked, checked while `checked` holds.
+pub struct Toggle {
+    action: ActionId,
+    checked: BoxedCondition,
+}
+
+impl Toggle {
+    pub fn new<M>(action: impl Into<ActionId>, checked: impl Condition<M>) -> Self {
+        Self {
+            action: action.into(),
+            checked: new_condition(checked),
+        }
+    }
+    pub fn action(&self) -> &ActionId {
+        &self.action
+    }
+    pub fn is_checked(&mut self, world: &mut World) -> bool {
+        self.checked.run_readonly((), world)
+    }
+}
+
+impl Debug for Toggle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<Toggle {}>", self.action)
+    }
+}
+
+impl MenuItemProvider for Toggle {
+    fn ui(&mut self, ui: &mut Ui, world: &mut World, name: &str) {
+        let mut checked = self.is_checked(world);
+        if ui.checkbox(&mut checked, name).clicked() {
+            run_menu_action(&self.action, world);
+        }
+    }
+    fn initialize(&mut self, world: &mut World) {
+        self.checked.initialize(world);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct View {
+        show_grid: bool,
+    }
+
+    #[derive(Resource, Reflect, Default, PartialEq, Debug)]
+    enum Gizmo {
+        #[default]
+        Move,
+        Rotate,
+        Custom(u32),
+    }
+
+    #[test]
+    fn test_checkable_items() {
+        let mut world = World::new();
+        world.init_resource::<View>();
+        world.init_resource::<Gizmo>();
+
+        let checkbox = Checkbox::from_resource(|view: &mut View| &mut view.show_grid);
+        assert!(!checkbox.is_checked(&mut world));
+        checkbox.set(&mut world, true);
+        assert!(world.resource::<View>().show_grid);
+
+        let radio = RadioGroup::from_resource::<Gizmo>();
+        assert_eq!(radio.variants(), ["Move", "Rotate"]);
+        radio.select(&mut world, "Rotate");
+        assert_eq!(*world.resource::<Gizmo>(), Gizmo::Rotate);
+        radio.select(&mut world, "Custom");
+        assert_eq!(radio.selected(&mut world).as_deref(), Some("Rotate"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Right-click menus built from the same [`ItemGroup`] model as the top bar.
+//!
+//! A context menu is registered for a [`ContextMenuId`] and shown for a [`ContextTarget`],
+//! the buttons of the menu run their action with the target as input (or `()` if the action takes none).
+//! Those actions are queued and run in [`PostUpdate`], after the dock has been shown,
+//! so that they can change the [`HeDockState`].
+
+use bevy::{prelude::*, utils::HashMap};
+use egui::{Response, Ui};
+
+use super::{Button, Custom, ItemGroup, MenuContext};
+use crate::{
+    action::{ActionError, ActionId, ActionRegistry, ActionsExt},
+    notifications::notify_action_result,
+    tab_system::{
+        areas::{dock_with_tab, DockAreas},
+        HeDockState, TabId,
+    },
+    utils::identifier::Identifier,
+    workspace::Workspaces,
+};
+
+pub const CLOSE_TAB: &str = "tab.close";
+pub const SPLIT_TAB_RIGHT: &str = "tab.split_right";
+pub const SPLIT_TAB_BELOW: &str = "tab.split_below";
+pub const FLOAT_TAB: &str = "tab.float";
+pub const MOVE_TAB_TO_WORKSPACE: &str = "tab.move_to_workspace";
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ContextMenuId {
+    /// The content of a tab.
+    Tab(TabId),
+    /// The header of every dock tab.
+    TabHeader,
+    /// A menu shown with [`show_context_menu`] on any widget.
+    Widget(Identifier),
+}
+
+/// What a context menu was opened on.
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub enum ContextTarget {
+    Tab(TabId),
+    Entity(Entity),
+    Widget(Identifier),
+}
+
+impl ContextTarget {
+    pub fn tab(&self) -> Option<&TabId> {
+        match self {
+            Self::Tab(tab) => Some(tab),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ContextMenus(HashMap<ContextMenuId, ItemGroup>);
+
+impl ContextMenus {
+    pub fn contains(&self, menu: &ContextMenuId) -> bool {
+        self.0.contains_key(menu)
+    }
+}
+
+/// The target of the context menu being shown, read by [`run_menu_action`](super::run_menu_action).
+#[derive(Resource, Default, Debug)]
+pub struct ActiveContextMenu(pub Option<ContextTarget>);
+
+/// An action queued from a context menu.
+#[derive(Event)]
+pub struct ContextAction {
+    pub action: ActionId,
+    pub input: Box<dyn Reflect>,
+}
+
+pub trait ContextMenuExt {
+    fn context_menu(
+        &mut self,
+        menu: ContextMenuId,
+        add_menu: impl FnOnce(MenuContext),
+    ) -> &mut Self;
+}
+
+impl ContextMenuExt for App {
+    fn context_menu(
+        &mut self,
+        menu: ContextMenuId,
+        add_menu: impl FnOnce(MenuContext),
+    ) -> &mut Self {
+        self.world_mut()
+            .resource_scope(|world, mut menus: Mut<ContextMenus>| {
+                add_menu(MenuContext {
+                    item: menus.0.entry(menu).or_default().as_container(),
+                    world,
+                });
+            });
+        self
+    }
+}
+
+/// Opens `menu` for `target` when `response` is right-clicked. Does nothing if the menu is not registered.
+pub fn show_context_menu(
+    response: &Response,
+    world: &mut World,
+    menu: &ContextMenuId,
+    target: ContextTarget,
+) {
+    if world
+        .get_resource::<ContextMenus>()
+        .is_some_and(|menus| menus.contains(menu))
+    {
+        response.context_menu(|ui| context_menu_ui(ui, world, menu, target));
+    }
+}
+
+/// Shows the items of `menu` into an already opened popup.
+pub fn context_menu_ui(
+    ui: &mut Ui,
+    world: &mut World,
+    menu: &ContextMenuId,
+    target: ContextTarget,
+) {
+    world.resource_scope(|world, mut menus: Mut<ContextMenus>| {
+        let Some(group) = menus.0.get_mut(menu) else {
+            return;
+        };
+        world.resource_mut::<ActiveContextMenu>().0 = Some(target);
+        group.foreach_ui(ui, world);
+        world.resource_mut::<ActiveContextMenu>().0 = None;
+    });
+}
+
+fn run_context_actions(world: &mut World) {
+    let requests: Vec<_> = world
+        .resource_mut::<Events<ContextAction>>()
+        .drain()
+        .collect();
+    for request in requests {
+        let result = world.resource_scope(|world, mut actions: Mut<ActionRegistry>| match actions
+            .run_instant_dyn(&request.action, request.input, world)
+        {
+            Err(ActionError::MismatchInput { .. }) => {
+                actions.run_instant(&request.action, (), world)
+            }
+            result => result,
+        });
+        notify_action_result(world, &request.action, &result);
+    }
+}
+
+fn target_tab(target: &ContextTarget) -> Result<TabId, String> {
+    target
+        .tab()
+        .cloned()
+        .ok_or_else(|| format!("{target:?} is not a tab"))
+}
+
+type Docks<'w> = (Option<ResMut<'w, HeDockState>>, Option<ResMut<'w, DockAreas>>);
+
+/// The dock state containing the tab, the main one or one of the [`DockAreas`].
+fn tab_dock<'a>(
+    tab: &TabId,
+    (state, areas): &'a mut Docks,
+) -> Result<&'a mut egui_dock::DockState<TabId>, String> {
+    dock_with_tab(tab, state.as_deref_mut(), areas.as_deref_mut())
+        .ok_or_else(|| "the tab is not opened".to_owned())
+}
+
+fn close_tab(In(target): In<ContextTarget>, mut docks: Docks) -> Result<(), String> {
+    let tab = target_tab(&target)?;
+    let state = tab_dock(&tab, &mut docks)?;
+    let index = state.find_tab(&tab).ok_or("the tab is not opened")?;
+    state.remove_tab(index);
+    Ok(())
+}
+
+fn split_tab(target: ContextTarget, mut docks: Docks, below: bool) -> Result<(), String> {
+    let tab = target_tab(&target)?;
+    let state = tab_dock(&tab, &mut docks)?;
+    let (surface, node, index) = state.find_tab(&tab).ok_or("the tab is not opened")?;
+    if state[surface][node].tabs_count() < 2 {
+        return Err("cannot split the only tab of a node".into());
+    }
+    state.remove_tab((surface, node, index));
+    if below {
+        state[surface].split_below(node, 0.5, vec![tab]);
+    } else {
+        state[surface].split_right(node, 0.5, vec![tab]);
+    }
+    Ok(())
+}
+
+/// In the main dock the floating window stays in the primary window, see [`DockAreas::add_floating`].
+fn float_tab(In(target): In<ContextTarget>, mut docks: Docks) -> Result<(), String> {
+    let tab = target_tab(&target)?;
+    if let (Some(main), Some(areas)) = (docks.0.as_deref_mut(), docks.1.as_deref_mut()) {
+        if let Some(index) = main.0.find_tab(&tab) {
+            main.0.remove_tab(index);
+            areas.add_floating(&mut main.0, vec![tab]);
+            return Ok(());
+        }
+    }
+    let state = tab_dock(&tab, &mut docks)?;
+    let index = state.find_tab(&tab).ok_or("the tab is not opened")?;
+    state.remove_tab(index);
+    state.add_window(vec![tab]);
+    Ok(())
+}
+
+fn move_tab_to_workspace(
+    In((tab, name)): In<(TabId, String)>,
+    state: Option<ResMut<HeDockState>>,
+    mut workspaces: ResMut<Workspaces>,
+) -> Result<(), String> {
+    if workspaces.current() == Some(name.as_str()) {
+        return Err(format!("{tab} is already in workspace {name}"));
+    }
+    let mut layout = workspaces
+        .get(&name)
+        .ok_or_else(|| format!("Workspace {name} does not exist."))?
+        .clone();
+    if layout.find_tab(&tab).is_none() {
+        layout.push_to_first_leaf(tab.clone());
+    }
+    workspaces.insert(name, layout);
+    if let Some(mut state) = state {
+        if let Some(index) = state.0.find_tab(&tab) {
+            state.0.remove_tab(index);
+        }
+    }
+    Ok(())
+}
+
+/// Lists the other workspaces, clicking one moves the tab the menu was opened on there.
+fn move_to_workspace_ui(ui: &mut Ui, world: &mut World, name: &str) {
+    let Some(tab) = world
+        .resource::<ActiveContextMenu>()
+        .0
+        .as_ref()
+        .and_then(ContextTarget::tab)
+        .cloned()
+    else {
+        return;
+    };
+    let Some(workspaces) = world.get_resource::<Workspaces>() else {
+        return;
+    };
+    let names: Vec<String> = workspaces
+        .names()
+        .filter(|name| workspaces.current() != Some(name))
+        .map(str::to_owned)
+        .collect();
+    ui.add_enabled_ui(!names.is_empty(), |ui| {
+        ui.menu_button(name, |ui| {
+            for workspace in names {
+                if ui.button(&workspace).clicked() {
+                    world.send_event(ContextAction {
+                        action: MOVE_TAB_TO_WORKSPACE.into(),
+                        input: Box::new((tab.clone(), workspace)),
+                    });
+                    ui.close_menu();
+                }
+            }
+        });
+    });
+}
+
+pub(crate) fn build_context_menus(app: &mut App) {
+    app.init_resource::<ContextMenus>()
+        .init_resource::<ActiveContextMenu>()
+        .add_event::<ContextAction>()
+        .register_action(CLOSE_TAB, "Close tab", close_tab)
+        .register_action(
+            SPLIT_TAB_RIGHT,
+            "Split tab right",
+            |In(target): In<ContextTarget>, docks: Docks| split_tab(target, docks, false),
+        )
+        .register_action(
+            SPLIT_TAB_BELOW,
+            "Split tab below",
+            |In(target): In<ContextTarget>, docks: Docks| split_tab(target, docks, true),
+        )
+        .register_action(FLOAT_TAB, "Float tab", float_tab)
+        .register_action(
+            MOVE_TAB_TO_WORKSPACE,
+            "Move tab to workspace",
+            move_tab_to_workspace,
+        )
+        .context_menu(ContextMenuId::TabHeader, |mut ctx| {
+            ctx.add("close", "Close".into(), Button::new(CLOSE_TAB), 0);
+            ctx.add(
+                "split_right",
+                "Split right".into(),
+                Button::new(SPLIT_TAB_RIGHT),
+                1,
+            );
+            ctx.add(
+                "split_below",
+                "Split below".into(),
+                Button::new(SPLIT_TAB_BELOW),
+                2,
+            );
+            ctx.add("float", "Float".into(), Button::new(FLOAT_TAB), 3);
+            ctx.add(
+                "move_to_workspace",
+                "Move to workspace".into(),
+                Custom(Box::new(move_to_workspace_ui)),
+                4,
+            );
+        })
+        .add_systems(PostUpdate, run_context_actions);
+}
+
+pub(crate) fn in_context_menu(world: &World) -> bool {
+    world
+        .get_resource::<ActiveContextMenu>()
+        .is_some_and(|active| active.0.is_some())
+}
+
+/// Queues `action` with the target of the context menu being shown, if any.
+pub(crate) fn queue_in_context(action: &ActionId, world: &mut World) -> bool {
+    let Some(target) = world
+        .get_resource::<ActiveContextMenu>()
+        .and_then(|active| active.0.clone())
+    else {
+        return false;
+    };
+    world.send_event(ContextAction {
+        action: action.clone(),
+        input: Box::new(target),
+    });
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use egui_dock::DockState;
+
+    use super::*;
+    use crate::{action::ActionPlugin, menu::MenuPlugin, workspace::WorkspacePlugin};
+
+    #[test]
+    fn test_tab_actions() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, WorkspacePlugin, MenuPlugin))
+            .insert_resource(HeDockState(DockState::new(vec![
+                "a".into(),
+                "b".into(),
+                "c".into(),
+            ])));
+        let world = app.world_mut();
+        let mut queue = |action: &str, input: Box<dyn Reflect>| {
+            world.send_event(ContextAction {
+                action: action.into(),
+                input,
+            });
+            run_context_actions(world);
+        };
+        queue(SPLIT_TAB_RIGHT, Box::new(ContextTarget::Tab("b".into())));
+        queue(FLOAT_TAB, Box::new(ContextTarget::Tab("c".into())));
+        queue(CLOSE_TAB, Box::new(ContextTarget::Widget("a".into())));
+
+        let state = &world.resource::<HeDockState>().0;
+        let (main, floating) = (state.main_surface(), state.find_tab(&"c".into()).unwrap());
+        assert_eq!(main.num_tabs(), 2);
+        assert_ne!(
+            main.find_tab(&"b".into()).unwrap().0,
+            main.find_tab(&"a".into()).unwrap().0
+        );
+        assert_ne!(floating.0, egui_dock::SurfaceIndex::main());
+    }
+}
@@ -0,0 +1,296 @@
+//! Menus defined in a RON file and merged into the menus built in code.
+//!
+//! ```ron
+//! [
+//!     (id: "file", name: "File", kind: SubMenu(items: [
+//!         (id: "save", name: "Save", priority: 1, kind: Button(action: "file.save")),
+//!     ])),
+//! ]
+//! ```
+//!
+//! A submenu or category with the id of an existing container of the same kind is merged into it,
+//! other items replace the existing one. Invalid items are skipped and reported, the rest of the file is still merged.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use super::{Button, Category, EditorMenuEntrys, MenuContext, MenuItemVariant, SubMenu};
+use crate::{
+    action::{ActionId, ActionRegistry},
+    notifications::notify_error,
+};
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MenuDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub priority: usize,
+    pub kind: MenuDefinitionKind,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum MenuDefinitionKind {
+    SubMenu {
+        #[serde(default)]
+        items: Vec<MenuDefinition>,
+    },
+    Category {
+        #[serde(default)]
+        items: Vec<MenuDefinition>,
+    },
+    Button {
+        action: ActionId,
+    },
+}
+
+#[derive(Snafu, Debug)]
+pub enum MenuDefinitionError {
+    #[snafu(display("cannot access menu file {}: {source}", path.display()))]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("cannot parse menu file: {source}"))]
+    Parse { source: ron::error::SpannedError },
+    #[snafu(display("menu item {path}: action {action} does not exist"))]
+    UnknownAction { path: String, action: String },
+    #[snafu(display("menu item {path} is defined more than once"))]
+    DuplicateId { path: String },
+    #[snafu(display("menu item {path} is not a container"))]
+    NotAContainer { path: String },
+    #[snafu(display("menu item {path} is not a {expected}"))]
+    KindMismatch {
+        path: String,
+        expected: &'static str,
+    },
+}
+
+pub fn load_menu_definition(path: &Path) -> Result<Vec<MenuDefinition>, MenuDefinitionError> {
+    let text = fs::read_to_string(path).context(IoSnafu { path })?;
+    ron::from_str(&text).context(ParseSnafu)
+}
+
+impl MenuContext<'_> {
+    /// Merges `definitions` into this container, returning the items that were skipped.
+    pub fn merge_definitions(
+        &mut self,
+        definitions: &[MenuDefinition],
+    ) -> Vec<MenuDefinitionError> {
+        let mut errors = Vec::new();
+        self.merge_with_path(definitions, "", &mut errors);
+        errors
+    }
+
+    fn merge_with_path(
+        &mut self,
+        definitions: &[MenuDefinition],
+        parent: &str,
+        errors: &mut Vec<MenuDefinitionError>,
+    ) {
+        let mut seen = Vec::new();
+        for definition in definitions {
+            let path = if parent.is_empty() {
+                definition.id.clone()
+            } else {
+                format!("{parent}.{}", definition.id)
+            };
+            if seen.contains(&&definition.id) {
+                errors.push(MenuDefinitionError::DuplicateId { path });
+                continue;
+            }
+            seen.push(&definition.id);
+            let name = definition.name.clone().into();
+            let items = match &definition.kind {
+                MenuDefinitionKind::Button { action } => {
+                    if self.world.resource::<ActionRegistry>().contains_key(action) {
+                        self.add(
+                            &definition.id,
+                            name,
+                            Button::new(action.clone()),
+                            definition.priority,
+                        );
+                    } else {
+                        errors.push(MenuDefinitionError::UnknownAction {
+                            path,
+                            action: action.to_string(),
+                        });
+                    }
+                    continue;
+                }
+                MenuDefinitionKind::SubMenu { items } | MenuDefinitionKind::Category { items } => {
+                    items
+                }
+            };
+            let is_sub_menu = matches!(definition.kind, MenuDefinitionKind::SubMenu { .. });
+            match self.item.remove_item(&definition.id) {
+                Some(mut existing) => {
+                    let same_kind = match existing.source {
+                        MenuItemVariant::SubMenu(_) => Some(is_sub_menu),
+                        MenuItemVariant::Category(_) => Some(!is_sub_menu),
+                        _ => None,
+                    };
+                    if same_kind == Some(true) {
+                        existing.name = name;
+                        existing.piority = definition.priority;
+                    }
+                    self.item.add_item(&definition.id, existing);
+                    match same_kind {
+                        Some(true) => {}
+                        Some(false) => {
+                            let expected = if is_sub_menu { "submenu" } else { "category" };
+                            errors.push(MenuDefinitionError::KindMismatch { path, expected });
+                            continue;
+                        }
+                        None => {
+                            errors.push(MenuDefinitionError::NotAContainer { path });
+                            continue;
+                        }
+                    }
+                }
+                None if is_sub_menu => self.add(
+                    &definition.id,
+                    name,
+                    SubMenu::default(),
+                    definition.priority,
+                ),
+                None => self.add(
+                    &definition.id,
+                    name,
+                    Category::default(),
+                    definition.priority,
+                ),
+            }
+            self.inside_sub(&definition.id, |mut sub| {
+                sub.merge_with_path(items, &path, errors)
+            })
+            .expect("the container has just been added");
+        }
+    }
+}
+
+/// Menu files merged into [`EditorMenuEntrys`] at startup,
+/// once every plugin has registered its actions.
+#[derive(Resource, Default, Debug)]
+pub struct MenuFiles {
+    pub paths: Vec<PathBuf>,
+    /// The errors met while merging the files, each one is also shown as an error toast.
+    pub errors: Vec<(PathBuf, MenuDefinitionError)>,
+}
+
+pub trait MenuFileExt {
+    fn add_menu_file(&mut self, path: impl Into<PathBuf>) -> &mut Self;
+}
+
+impl MenuFileExt for App {
+    fn add_menu_file(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<MenuFiles>()
+            .paths
+            .push(path.into());
+        self
+    }
+}
+
+pub(crate) fn load_menu_files(world: &mut World) {
+    let files = std::mem::take(&mut world.resource_mut::<MenuFiles>().paths);
+    for path in files {
+        let errors = match load_menu_definition(&path) {
+            Ok(definitions) => world.resource_scope(|world, mut entrys: Mut<EditorMenuEntrys>| {
+                MenuContext {
+                    item: entrys.as_container(),
+                    world,
+                }
+                .merge_definitions(&definitions)
+            }),
+            Err(err) => vec![err],
+        };
+        for err in errors {
+            notify_error(
+                world,
+                t!("menu.file_error", path = path.display(), error = err),
+            );
+            world
+                .resource_mut::<MenuFiles>()
+                .errors
+                .push((path.clone(), err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        action::{ActionPlugin, ActionsExt},
+        menu::{MenuExt, MenuItemProvider, MenuPlugin},
+    };
+
+    #[test]
+    fn test_missing_menu_file() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, MenuPlugin))
+            .add_menu_file("missing_menu_file.ron");
+        app.update();
+        let files = app.world().resource::<MenuFiles>();
+        assert!(matches!(
+            files.errors.as_slice(),
+            [(_, MenuDefinitionError::Io { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_merge_definitions() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, MenuPlugin))
+            .register_action("file.save", "Save", || {})
+            .register_action("file.quit", "Quit", || {})
+            .menu_context(|mut ctx| {
+                ctx.with_sub_menu("file", "File".into(), 0, |mut ctx| {
+                    ctx.add("quit", "Quit".into(), Button::new("file.quit"), 9);
+                    ctx.with_category("recent", "Recent".into(), 5, |_| {});
+                });
+                ctx.add("help", "Help".into(), Button::new("file.quit"), 1);
+            });
+        let definitions: Vec<MenuDefinition> = ron::from_str(
+            r#"[
+                (id: "file", name: "File", kind: SubMenu(items: [
+                    (id: "save", name: "Save", kind: Button(action: "file.save")),
+                    (id: "export", name: "Export", kind: Button(action: "file.export")),
+                    (id: "save", name: "Save again", kind: Button(action: "file.save")),
+                    (id: "recent", name: "Recent", kind: SubMenu(items: [])),
+                ])),
+                (id: "help", name: "Help", kind: Category(items: [])),
+            ]"#,
+        )
+        .unwrap();
+
+        let mut errors = Vec::new();
+        app.menu_context(|mut ctx| errors = ctx.merge_definitions(&definitions));
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "menu item file.export: action file.export does not exist",
+                "menu item file.save is defined more than once",
+                "menu item file.recent is not a submenu",
+                "menu item help is not a container",
+            ]
+        );
+        let mut entrys = app.world_mut().resource_mut::<EditorMenuEntrys>();
+        let file = entrys.items.get_mut("file").unwrap();
+        assert!(file.source.find_subitem_mut("save").is_some());
+        assert!(file.source.find_subitem_mut("quit").is_some());
+        assert!(matches!(
+            file.source.find_subitem_mut("recent").unwrap().source,
+            MenuItemVariant::Category(_)
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+//! Menu items generated at render time by a system, for recent files, open tabs and the like.
+
+use std::fmt::Debug;
+
+use bevy::{
+    ecs::schedule::{BoxedCondition, Condition},
+    prelude::*,
+};
+use egui::Ui;
+
+use super::{run_menu_action_with, MenuItemProvider};
+use crate::{action::ActionId, utils::new_condition};
+
+/// A button produced by the system of a [`Dynamic`] item.
+#[derive(Debug)]
+pub struct DynamicEntry {
+    pub label: String,
+    pub action: ActionId,
+    /// Cloned as the input of the action on every click.
+    pub input: Box<dyn Reflect>,
+    pub enabled: bool,
+}
+
+impl DynamicEntry {
+    pub fn new(label: impl Into<String>, action: impl Into<ActionId>) -> Self {
+        Self {
+            label: label.into(),
+            action: action.into(),
+            input: Box::new(()),
+            enabled: true,
+        }
+    }
+    pub fn with_input(mut self, input: impl Reflect) -> Self {
+        self.input = Box::new(input);
+        self
+    }
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Shows the entries returned by a system. Without [`Dynamic::rebuild_when`] the system runs every time the item is shown,
+/// otherwise only the first time and when the condition holds.
+pub struct Dynamic {
+    producer: Box<dyn System<In = (), Out = Vec<DynamicEntry>>>,
+    rebuild_when: Option<BoxedCondition>,
+    entries: Option<Vec<DynamicEntry>>,
+}
+
+impl Dynamic {
+    pub fn new<M>(producer: impl IntoSystem<(), Vec<DynamicEntry>, M>) -> Self {
+        Self {
+            producer: Box::new(IntoSystem::into_system(producer)),
+            rebuild_when: None,
+            entries: None,
+        }
+    }
+    /// Caches the entries until `condition` holds, for example [`resource_changed`] on the inputs of the system.
+    pub fn rebuild_when<M>(mut self, condition: impl Condition<M>) -> Self {
+        self.rebuild_when = Some(new_condition(condition));
+        self
+    }
+    /// The entries, rebuilt if needed.
+    pub fn entries(&mut self, world: &mut World) -> &[DynamicEntry] {
+        // The condition is evaluated even when building for the first time, so that it sees changes from then on.
+        let changed = match &mut self.rebuild_when {
+            Some(condition) => condition.run((), world),
+            None => true,
+        };
+        if changed || self.entries.is_none() {
+            let entries = self.producer.run((), world);
+            self.producer.apply_deferred(world);
+            self.entries = Some(entries);
+        }
+        self.entries.as_deref().unwrap_or_default()
+    }
+}
+
+impl Debug for Dynamic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dynamic")
+            .field("producer", &self.producer.name())
+            .field("entries", &self.entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MenuItemProvider for Dynamic {
+    fn ui(&mut self, ui: &mut Ui, world: &mut World, _name: &str) {
+        let mut clicked = None;
+        for (i, entry) in self.entries(world).iter().enumerate() {
+            if ui
+                .add_enabled(entry.enabled, egui::Button::new(&entry.label))
+                .clicked()
+            {
+                clicked = Some(i);
+            }
+        }
+        if let Some(entry) = clicked.and_then(|i| self.entries.as_ref()?.get(i)) {
+            let (action, input) = (entry.action.clone(), entry.input.clone_value());
+            run_menu_action_with(&action, input, world);
+            ui.close_menu();
+        }
+    }
+    fn initialize(&mut self, world: &mut World) {
+        self.producer.initialize(world);
+        if let Some(condition) = &mut self.rebuild_when {
+            condition.initialize(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct RecentFiles(Vec<String>);
+
+    #[test]
+    fn test_dynamic_cache() {
+        let mut world = World::new();
+        world.init_resource::<RecentFiles>();
+        let mut item = Dynamic::new(|files: Res<RecentFiles>, mut builds: Local<usize>| {
+            *builds += 1;
+            files
+                .0
+                .iter()
+                .map(|file| DynamicEntry::new(format!("{file} ({})", *builds), "file.open"))
+                .collect()
+        })
+        .rebuild_when(resource_changed::<RecentFiles>);
+        item.initialize(&mut world);
+
+        let labels = |item: &mut Dynamic, world: &mut World| {
+            let entries = item.entries(world);
+            entries
+                .iter()
+                .map(|entry| entry.label.clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(labels(&mut item, &mut world).is_empty());
+        world.resource_mut::<RecentFiles>().0.push("a.txt".into());
+        assert_eq!(labels(&mut item, &mut world), ["a.txt (2)"]);
+        assert_eq!(labels(&mut item, &mut world), ["a.txt (2)"]);
+    }
+}
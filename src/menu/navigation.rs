@@ -0,0 +1,845 @@
+//! Keyboard navigation of the menu bar.
+//!
+//! Alt (pressed and released alone) or F10 focuses the bar, the arrows move between and into menus,
+//! Enter or Space activates, Esc closes one level. A letter after `&` in an item name (`&File`, `Save &As`)
+//! is its mnemonic: typing it selects and activates the item, Alt+letter opens a menu of the bar directly.
+//! `&&` shows a literal `&`.
+//!
+//! While the bar is focused, the menus are drawn by this module instead of egui's popups,
+//! and the keys pressed are consumed from [`ButtonInput<KeyCode>`] so that they do not trigger hotkeys.
+//! Inside menus, each entry of a [`Dynamic`](super::Dynamic) item and each variant of a
+//! [`RadioGroup`](super::RadioGroup) is a row of its own.
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use egui::{text::LayoutJob, Event, Key, Modifiers, Rect, TextFormat, TextStyle, Ui, WidgetText};
+
+use super::{
+    run_menu_action, run_menu_action_with, EditorMenuEntrys, ItemGroup, MenuItem, MenuItemProvider,
+    MenuItemVariant,
+};
+use crate::hotkeys::HotkeyRegistry;
+
+/// The label of an item name without its mnemonic marker, and the mnemonic.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mnemonic {
+    pub label: String,
+    /// Lowercase.
+    pub key: Option<char>,
+    /// Byte index of the mnemonic in `label`.
+    pub index: Option<usize>,
+}
+
+impl Mnemonic {
+    pub fn parse(name: &str) -> Self {
+        let mut mnemonic = Self {
+            label: String::with_capacity(name.len()),
+            key: None,
+            index: None,
+        };
+        let mut chars = name.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('&', Some('&')) => {
+                    chars.next();
+                    mnemonic.label.push('&');
+                }
+                ('&', Some(&next)) if mnemonic.key.is_none() => {
+                    mnemonic.key = next.to_lowercase().next();
+                    mnemonic.index = Some(mnemonic.label.len());
+                }
+                _ => mnemonic.label.push(c),
+            }
+        }
+        mnemonic
+    }
+}
+
+/// `name` without its mnemonic marker.
+pub fn menu_label(name: &str) -> Cow<'_, str> {
+    if name.contains('&') {
+        Mnemonic::parse(name).label.into()
+    } else {
+        name.into()
+    }
+}
+
+fn mnemonic_text(name: &str, ui: &Ui) -> WidgetText {
+    let mnemonic = Mnemonic::parse(name);
+    let Some(index) = mnemonic.index else {
+        return mnemonic.label.into();
+    };
+    let key_len = mnemonic.label[index..]
+        .chars()
+        .next()
+        .map_or(0, |c| c.len_utf8());
+    let font_id = TextStyle::Button.resolve(ui.style());
+    let color = ui.visuals().text_color();
+    let plain = TextFormat::simple(font_id.clone(), color);
+    let underlined = TextFormat {
+        underline: egui::Stroke::new(1.0, color),
+        ..plain.clone()
+    };
+    let mut job = LayoutJob::default();
+    job.append(&mnemonic.label[..index], 0.0, plain.clone());
+    job.append(&mnemonic.label[index..index + key_len], 0.0, underlined);
+    job.append(&mnemonic.label[index + key_len..], 0.0, plain);
+    job.into()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NavigationKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Letter(char),
+}
+
+/// What the navigation knows of an item: its mnemonic and whether it opens a submenu.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NavigationEntry {
+    pub mnemonic: Option<char>,
+    pub submenu: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NavigationOutcome {
+    None,
+    Close,
+    /// Activate the item at this index of the deepest level.
+    Activate(usize),
+}
+
+/// The state of keyboard navigation, `path` holds the selected index of the bar then of each open menu.
+#[derive(Resource, Default, Debug)]
+pub struct MenuNavigation {
+    path: Vec<usize>,
+    alt_armed: bool,
+    alt_down: bool,
+    rects: Vec<Rect>,
+}
+
+impl MenuNavigation {
+    pub fn is_active(&self) -> bool {
+        !self.path.is_empty()
+    }
+    /// Focuses the bar, selecting its first item.
+    pub fn activate(&mut self) {
+        self.path = vec![0];
+    }
+    pub fn close(&mut self) {
+        self.path.clear();
+    }
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+    /// Applies `key`, `levels` holds the entries of the bar then of each open menu.
+    pub fn step(
+        &mut self,
+        key: NavigationKey,
+        levels: &[Vec<NavigationEntry>],
+    ) -> NavigationOutcome {
+        let Some(bar) = levels.first().filter(|bar| !bar.is_empty()) else {
+            self.close();
+            return NavigationOutcome::None;
+        };
+        let Some(depth) = self.path.len().checked_sub(1) else {
+            return NavigationOutcome::None;
+        };
+        let Some(current) = levels.get(depth) else {
+            return NavigationOutcome::None;
+        };
+        let selected = self.path[depth];
+        let cycle = |index: usize, delta: isize, len: usize| {
+            (index as isize + delta).rem_euclid(len.max(1) as isize) as usize
+        };
+        let open_or_activate = |path: &mut Vec<usize>, index: usize| {
+            if current.get(index).is_some_and(|entry| entry.submenu) {
+                path.push(0);
+                NavigationOutcome::None
+            } else {
+                NavigationOutcome::Activate(index)
+            }
+        };
+        match key {
+            NavigationKey::Escape => {
+                self.path.pop();
+                if self.path.is_empty() {
+                    return NavigationOutcome::Close;
+                }
+            }
+            NavigationKey::Right
+                if depth >= 1 && current.get(selected).is_some_and(|entry| entry.submenu) =>
+            {
+                self.path.push(0);
+            }
+            NavigationKey::Left if depth >= 2 => {
+                self.path.pop();
+            }
+            NavigationKey::Left | NavigationKey::Right => {
+                let delta = if key == NavigationKey::Left { -1 } else { 1 };
+                let top = cycle(self.path[0], delta, bar.len());
+                let open = depth >= 1 && bar[top].submenu;
+                self.path = vec![top];
+                if open {
+                    self.path.push(0);
+                }
+            }
+            NavigationKey::Up | NavigationKey::Down if depth == 0 => {
+                return open_or_activate(&mut self.path, selected);
+            }
+            NavigationKey::Up | NavigationKey::Down => {
+                let delta = if key == NavigationKey::Up { -1 } else { 1 };
+                self.path[depth] = cycle(selected, delta, current.len());
+            }
+            NavigationKey::Enter => return open_or_activate(&mut self.path, selected),
+            NavigationKey::Letter(letter) => {
+                if let Some(index) = current
+                    .iter()
+                    .position(|entry| entry.mnemonic == Some(letter))
+                {
+                    self.path[depth] = index;
+                    return open_or_activate(&mut self.path, index);
+                }
+            }
+        }
+        NavigationOutcome::None
+    }
+}
+
+fn is_navigable(item: &MenuItem) -> bool {
+    matches!(
+        item.source,
+        MenuItemVariant::Button(_)
+            | MenuItemVariant::Checkbox(_)
+            | MenuItemVariant::Toggle(_)
+            | MenuItemVariant::SubMenu(_)
+    )
+}
+
+/// A row of an item shown as several rows: an entry of a dynamic item or a variant of a radio group.
+struct Part {
+    label: String,
+    enabled: bool,
+    shortcut: String,
+}
+
+/// The rows of a dynamic item or a radio group, `None` for other items.
+fn parts(item: &mut MenuItem, world: &mut World) -> Option<Vec<Part>> {
+    match &mut item.source {
+        MenuItemVariant::Dynamic(dynamic) => Some(
+            dynamic
+                .entries(world)
+                .iter()
+                .map(|entry| Part {
+                    label: entry.label.clone(),
+                    enabled: entry.enabled,
+                    shortcut: String::new(),
+                })
+                .collect(),
+        ),
+        MenuItemVariant::RadioGroup(radio) => {
+            let selected = radio.selected(world);
+            Some(
+                radio
+                    .variants()
+                    .iter()
+                    .map(|variant| Part {
+                        label: variant.to_string(),
+                        enabled: true,
+                        shortcut: if selected.as_deref() == Some(*variant) {
+                            "●"
+                        } else {
+                            ""
+                        }
+                        .to_owned(),
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// A navigable row: the indices leading to an item through categories,
+/// and the index of the part for an item shown as several rows.
+struct RowRef {
+    chain: Vec<usize>,
+    part: Option<usize>,
+}
+
+enum Row<'a> {
+    Separator,
+    Item(&'a mut MenuItem),
+}
+
+/// The visible items of `group`, with the content of categories inlined if `flatten`.
+fn rows<'a>(group: &'a mut ItemGroup, flatten: bool, out: &mut Vec<Row<'a>>) {
+    for item in group.items.values_mut().filter(|item| !item.hidden) {
+        if flatten && matches!(item.source, MenuItemVariant::Category(_)) {
+            let MenuItemVariant::Category(category) = &mut item.source else {
+                unreachable!()
+            };
+            out.push(Row::Separator);
+            rows(&mut category.group, flatten, out);
+        } else {
+            out.push(Row::Item(item));
+        }
+    }
+}
+
+fn collect_rows(group: &mut ItemGroup, flatten: bool) -> Vec<Row<'_>> {
+    let mut out = Vec::new();
+    rows(group, flatten, &mut out);
+    out
+}
+
+/// The navigable rows of `group` in display order. Items are only split into parts inside menus (`flatten`).
+fn navigable(group: &mut ItemGroup, flatten: bool, world: &mut World) -> Vec<RowRef> {
+    let mut out = Vec::new();
+    for (index, item) in group.items.values_mut().enumerate() {
+        if item.hidden {
+            continue;
+        }
+        if let (true, MenuItemVariant::Category(category)) = (flatten, &mut item.source) {
+            out.extend(
+                navigable(&mut category.group, flatten, world)
+                    .into_iter()
+                    .map(|mut row| {
+                        row.chain.insert(0, index);
+                        row
+                    }),
+            );
+        } else if let Some(parts) = parts(item, world).filter(|_| flatten) {
+            out.extend((0..parts.len()).map(|part| RowRef {
+                chain: vec![index],
+                part: Some(part),
+            }));
+        } else if is_navigable(item) {
+            out.push(RowRef {
+                chain: vec![index],
+                part: None,
+            });
+        }
+    }
+    out
+}
+
+fn item_at<'a>(mut group: &'a mut ItemGroup, chain: &[usize]) -> Option<&'a mut MenuItem> {
+    let (last, categories) = chain.split_last()?;
+    for index in categories {
+        match &mut group.items.get_index_mut(*index)?.1.source {
+            MenuItemVariant::Category(category) => group = &mut category.group,
+            _ => return None,
+        }
+    }
+    group.items.get_index_mut(*last).map(|(_, item)| item)
+}
+
+/// The item of the `index`-th navigable row of `group`, and the part of the item that row shows.
+fn navigable_at<'a>(
+    group: &'a mut ItemGroup,
+    flatten: bool,
+    index: usize,
+    world: &mut World,
+) -> Option<(&'a mut MenuItem, Option<usize>)> {
+    let row = navigable(group, flatten, world).into_iter().nth(index)?;
+    item_at(group, &row.chain).map(|item| (item, row.part))
+}
+
+fn entries(group: &mut ItemGroup, flatten: bool, world: &mut World) -> Vec<NavigationEntry> {
+    let mut entries = Vec::new();
+    for row in navigable(group, flatten, world) {
+        if let Some(item) = item_at(group, &row.chain) {
+            entries.push(NavigationEntry {
+                // Dynamic entries and variant names are data, not names with a mnemonic.
+                mnemonic: row
+                    .part
+                    .is_none()
+                    .then(|| Mnemonic::parse(&item.name).key)
+                    .flatten(),
+                submenu: matches!(item.source, MenuItemVariant::SubMenu(_)),
+            });
+        }
+    }
+    entries
+}
+
+/// The group of the submenu at `index` among the navigable rows of `group`.
+fn submenu_at<'a>(
+    group: &'a mut ItemGroup,
+    flatten: bool,
+    index: usize,
+    world: &mut World,
+) -> Option<&'a mut ItemGroup> {
+    match &mut navigable_at(group, flatten, index, world)?.0.source {
+        MenuItemVariant::SubMenu(sub_menu) => Some(&mut sub_menu.group),
+        _ => None,
+    }
+}
+
+/// The entries of the bar and of each open menu, truncating `path` where it no longer leads to a submenu.
+fn levels(
+    bar: &mut ItemGroup,
+    path: &mut Vec<usize>,
+    world: &mut World,
+) -> Vec<Vec<NavigationEntry>> {
+    let mut levels = vec![entries(bar, false, world)];
+    let mut group = bar;
+    for depth in 0..path.len() {
+        let len = levels[depth].len();
+        if len == 0 {
+            path.truncate(depth.max(1));
+            break;
+        }
+        path[depth] = path[depth].min(len - 1);
+        if depth + 1 == path.len() {
+            break;
+        }
+        match submenu_at(group, depth > 0, path[depth], world) {
+            Some(sub) => {
+                levels.push(entries(sub, true, world));
+                group = sub;
+            }
+            None => {
+                path.truncate(depth + 1);
+                break;
+            }
+        }
+    }
+    levels
+}
+
+fn activate_item(item: &mut MenuItem, part: Option<usize>, world: &mut World) {
+    match (&mut item.source, part) {
+        (MenuItemVariant::Dynamic(dynamic), Some(part)) => {
+            let Some(entry) = dynamic
+                .entries(world)
+                .get(part)
+                .filter(|entry| entry.enabled)
+            else {
+                return;
+            };
+            let (action, input) = (entry.action.clone(), entry.input.clone_value());
+            run_menu_action_with(&action, input, world);
+        }
+        (MenuItemVariant::RadioGroup(radio), Some(part)) => {
+            if let Some(variant) = radio.variants().get(part).copied() {
+                radio.select(world, variant);
+            }
+        }
+        (MenuItemVariant::Button(button), _) => button.activate(world),
+        (MenuItemVariant::Checkbox(checkbox), _) => {
+            let checked = checkbox.is_checked(world);
+            checkbox.set(world, !checked);
+        }
+        (MenuItemVariant::Toggle(toggle), _) => run_menu_action(toggle.action(), world),
+        _ => {}
+    }
+}
+
+fn letter(key: Key) -> Option<char> {
+    let mut name = key.name().chars();
+    match (name.next(), name.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+/// Reads the keys of this frame, returns whether the bar was toggled and the navigation key.
+fn read_input(ui: &Ui, nav: &mut MenuNavigation) -> (bool, Option<NavigationKey>, Option<char>) {
+    let typing = ui.ctx().wants_keyboard_input();
+    ui.ctx().input_mut(|input| {
+        let mut toggle = false;
+        let mut alt_letter = None;
+        let mut key = None;
+        let alt = input.modifiers.alt;
+        let any_key = input
+            .events
+            .iter()
+            .any(|event| matches!(event, Event::Key { pressed: true, .. }));
+        if alt && !nav.alt_down {
+            nav.alt_armed = !any_key;
+        } else if alt && (any_key || input.pointer.any_pressed()) {
+            nav.alt_armed = false;
+        } else if !alt && nav.alt_down && nav.alt_armed {
+            toggle = true;
+            nav.alt_armed = false;
+        }
+        nav.alt_down = alt;
+        if input.consume_key(Modifiers::NONE, Key::F10) {
+            toggle = true;
+        }
+        if typing {
+            return (false, None, None);
+        }
+        if !nav.is_active() {
+            for event in &input.events {
+                if let Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } = event
+                {
+                    if modifiers.alt && !modifiers.ctrl && !modifiers.command {
+                        alt_letter = alt_letter.or(letter(*key));
+                    }
+                }
+            }
+            return (toggle, None, alt_letter);
+        }
+        for (egui_key, nav_key) in [
+            (Key::ArrowUp, NavigationKey::Up),
+            (Key::ArrowDown, NavigationKey::Down),
+            (Key::ArrowLeft, NavigationKey::Left),
+            (Key::ArrowRight, NavigationKey::Right),
+            (Key::Enter, NavigationKey::Enter),
+            (Key::Space, NavigationKey::Enter),
+            (Key::Escape, NavigationKey::Escape),
+        ] {
+            if key.is_none() && input.consume_key(Modifiers::NONE, egui_key) {
+                key = Some(nav_key);
+            }
+        }
+        if key.is_none() {
+            key = input.events.iter().find_map(|event| match event {
+                Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if !modifiers.ctrl && !modifiers.command => {
+                    letter(*key).map(NavigationKey::Letter)
+                }
+                _ => None,
+            });
+        }
+        (toggle, key, None)
+    })
+}
+
+/// Keys handled by the menu must not trigger hotkeys.
+fn consume_keyboard(world: &mut World) {
+    if let Some(mut input) = world.get_resource_mut::<ButtonInput<KeyCode>>() {
+        let pressed: Vec<_> = input.get_just_pressed().copied().collect();
+        for key in pressed {
+            input.clear_just_pressed(key);
+        }
+    }
+}
+
+pub(crate) fn menu_bar_ui(ui: &mut Ui, world: &mut World) {
+    world.resource_scope(|world, mut entrys: Mut<EditorMenuEntrys>| {
+        super::search::update_search(&mut entrys.0, world);
+        world.resource_scope(|world, mut nav: Mut<MenuNavigation>| {
+            let was_active = nav.is_active();
+            let (toggle, key, alt_letter) = read_input(ui, &mut nav);
+            if toggle {
+                if nav.is_active() {
+                    nav.close();
+                } else {
+                    nav.activate();
+                }
+            }
+            let mut outcome = NavigationOutcome::None;
+            if let Some(letter) = alt_letter {
+                let bar = levels(&mut entrys.0, &mut vec![0], world);
+                if let Some(index) = bar[0]
+                    .iter()
+                    .position(|entry| entry.mnemonic == Some(letter))
+                {
+                    nav.path = vec![index];
+                    outcome = nav.step(NavigationKey::Enter, &bar);
+                }
+            }
+            if let (true, Some(key)) = (nav.is_active(), key) {
+                let levels = levels(&mut entrys.0, &mut nav.path, world);
+                outcome = nav.step(key, &levels);
+            }
+            match outcome {
+                NavigationOutcome::Activate(index) => {
+                    activate_at(&mut entrys.0, &nav.path, index, world);
+                    nav.close();
+                }
+                NavigationOutcome::Close => nav.close(),
+                NavigationOutcome::None => {}
+            }
+            if was_active || nav.is_active() {
+                consume_keyboard(world);
+            }
+            if nav.is_active() {
+                let pointer_outside = ui.ctx().input(|input| {
+                    input.pointer.any_pressed()
+                        && input
+                            .pointer
+                            .interact_pos()
+                            .is_some_and(|pos| !nav.rects.iter().any(|rect| rect.contains(pos)))
+                });
+                if pointer_outside {
+                    nav.close();
+                }
+            }
+            if nav.is_active() {
+                show_navigated(ui, world, &mut entrys.0, &mut nav);
+            } else {
+                entrys.0.foreach_ui(ui, world);
+            }
+        });
+    });
+}
+
+/// Activates the item at `index` of the deepest level of `path`.
+fn activate_at(bar: &mut ItemGroup, path: &[usize], index: usize, world: &mut World) {
+    let mut group = bar;
+    for (depth, selected) in path[..path.len() - 1].iter().enumerate() {
+        let Some(sub) = submenu_at(group, depth > 0, *selected, world) else {
+            return;
+        };
+        group = sub;
+    }
+    if let Some((item, part)) = navigable_at(group, path.len() > 1, index, world) {
+        activate_item(item, part, world);
+    }
+}
+
+fn show_navigated(ui: &mut Ui, world: &mut World, bar: &mut ItemGroup, nav: &mut MenuNavigation) {
+    let mut rects = Vec::new();
+    let mut clicked: Option<(usize, usize)> = None;
+    let mut anchor = None;
+    {
+        let mut rows = collect_rows(bar, false);
+        let mut index = 0;
+        for row in &mut rows {
+            let Row::Item(item) = row else { continue };
+            if !is_navigable(item) {
+                item.source.ui(ui, world, &menu_label(&item.name));
+                continue;
+            }
+            let selected = nav.path[0] == index;
+            let response = ui.selectable_label(selected, mnemonic_text(&item.name, ui));
+            if response.clicked() {
+                clicked = Some((0, index));
+            }
+            if selected {
+                anchor = Some(response.rect.left_bottom());
+            }
+            rects.push(response.rect);
+            index += 1;
+        }
+    }
+    let mut group: &mut ItemGroup = bar;
+    for depth in 1..nav.path.len() {
+        let Some(sub) = submenu_at(group, depth > 1, nav.path[depth - 1], world) else {
+            break;
+        };
+        group = sub;
+        let Some(pos) = anchor.take() else { break };
+        let area = egui::Area::new(ui.id().with(("menu_navigation", depth)))
+            .order(egui::Order::Foreground)
+            .fixed_pos(pos)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::menu(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(120.0);
+                    ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                        let mut rows = collect_rows(group, true);
+                        let mut index = 0;
+                        for row in &mut rows {
+                            let item = match row {
+                                Row::Separator => {
+                                    ui.separator();
+                                    continue;
+                                }
+                                Row::Item(item) => item,
+                            };
+                            if let Some(parts) = parts(item, world) {
+                                if matches!(item.source, MenuItemVariant::RadioGroup(_))
+                                    && !item.name.is_empty()
+                                {
+                                    ui.label(menu_label(&item.name));
+                                }
+                                for part in parts {
+                                    let response = ui.add_enabled(
+                                        part.enabled,
+                                        egui::Button::new(part.label)
+                                            .selected(nav.path[depth] == index)
+                                            .shortcut_text(part.shortcut),
+                                    );
+                                    if response.clicked() {
+                                        clicked = Some((depth, index));
+                                    }
+                                    index += 1;
+                                }
+                                continue;
+                            }
+                            if !is_navigable(item) {
+                                item.source.ui(ui, world, &menu_label(&item.name));
+                                continue;
+                            }
+                            let selected = nav.path[depth] == index;
+                            let response = navigation_row(ui, world, item, selected);
+                            if response.clicked() {
+                                clicked = Some((depth, index));
+                            }
+                            if selected {
+                                anchor = Some(response.rect.right_top());
+                            }
+                            index += 1;
+                        }
+                    });
+                });
+            });
+        rects.push(area.response.rect);
+    }
+    nav.rects = rects;
+    if let Some((depth, index)) = clicked {
+        nav.path.truncate(depth + 1);
+        nav.path[depth] = index;
+        let levels = levels(bar, &mut nav.path, world);
+        if let NavigationOutcome::Activate(index) = nav.step(NavigationKey::Enter, &levels) {
+            activate_at(bar, &nav.path, index, world);
+            nav.close();
+        }
+    }
+}
+
+fn navigation_row(
+    ui: &mut Ui,
+    world: &mut World,
+    item: &mut MenuItem,
+    selected: bool,
+) -> egui::Response {
+    let check = |checked: bool| if checked { "✔" } else { "" }.to_owned();
+    let (enabled, shortcut) = match &mut item.source {
+        MenuItemVariant::Button(button) => (
+            button.is_available(world),
+            world
+                .get_resource::<HotkeyRegistry>()
+                .and_then(|hotkeys| hotkeys.shortcut_text(&button.action))
+                .unwrap_or_default(),
+        ),
+        MenuItemVariant::Checkbox(checkbox) => (true, check(checkbox.is_checked(world))),
+        MenuItemVariant::Toggle(toggle) => (true, check(toggle.is_checked(world))),
+        _ => (true, "⏵".to_owned()),
+    };
+    ui.add_enabled(
+        enabled,
+        egui::Button::new(mnemonic_text(&item.name, ui))
+            .selected(selected)
+            .shortcut_text(shortcut),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        action::{ActionPlugin, ActionsExt},
+        menu::{Dynamic, DynamicEntry, MenuExt, MenuPlugin, RadioGroup},
+    };
+
+    #[derive(Resource, Reflect, Default, PartialEq, Debug)]
+    enum Gizmo {
+        #[default]
+        Move,
+        Rotate,
+    }
+
+    #[derive(Resource, Default)]
+    struct Closed(Vec<String>);
+
+    #[test]
+    fn test_mnemonic() {
+        let mnemonic = Mnemonic::parse("Save &As && Close");
+        assert_eq!(mnemonic.label, "Save As & Close");
+        assert_eq!((mnemonic.key, mnemonic.index), (Some('a'), Some(5)));
+        assert_eq!(Mnemonic::parse("Plain").key, None);
+    }
+
+    #[test]
+    fn test_navigation_steps() {
+        let entry = |mnemonic, submenu| NavigationEntry {
+            mnemonic: Some(mnemonic),
+            submenu,
+        };
+        let bar = vec![entry('f', true), entry('e', true), entry('q', false)];
+        let file = vec![entry('n', false), entry('r', true)];
+        let recent = vec![entry('x', false)];
+        let levels = [bar, file, recent];
+        let mut nav = MenuNavigation::default();
+        nav.activate();
+
+        let outcome = nav.step(NavigationKey::Down, &levels[..1]);
+        assert_eq!(
+            (outcome, nav.path()),
+            (NavigationOutcome::None, &[0, 0][..])
+        );
+        nav.step(NavigationKey::Up, &levels[..2]);
+        assert_eq!(nav.path(), [0, 1]);
+        nav.step(NavigationKey::Right, &levels[..2]);
+        assert_eq!(nav.path(), [0, 1, 0]);
+        nav.step(NavigationKey::Escape, &levels);
+        nav.step(NavigationKey::Left, &levels[..2]);
+        assert_eq!(nav.path(), [2]);
+        nav.step(NavigationKey::Letter('f'), &levels[..1]);
+        assert_eq!(nav.path(), [0, 0]);
+        let outcome = nav.step(NavigationKey::Letter('n'), &levels[..2]);
+        assert_eq!(outcome, NavigationOutcome::Activate(0));
+        nav.close();
+        nav.activate();
+        let outcome = nav.step(NavigationKey::Escape, &levels[..1]);
+        assert_eq!(outcome, NavigationOutcome::Close);
+    }
+
+    #[test]
+    fn test_navigate_parts() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, MenuPlugin))
+            .init_resource::<Gizmo>()
+            .init_resource::<Closed>()
+            .register_action(
+                "tab.close",
+                "Close tab",
+                |In(tab): In<String>, mut closed: ResMut<Closed>| closed.0.push(tab),
+            )
+            .menu_context(|mut ctx| {
+                ctx.with_sub_menu("view", "&View".into(), 0, |mut ctx| {
+                    ctx.add(
+                        "gizmo",
+                        "Gizmo".into(),
+                        RadioGroup::from_resource::<Gizmo>(),
+                        0,
+                    );
+                    ctx.with_sub_menu("close", "&Close".into(), 1, |mut ctx| {
+                        let tabs = Dynamic::new(|| {
+                            vec![
+                                DynamicEntry::new("A", "tab.close").with_input("a".to_owned()),
+                                DynamicEntry::new("B", "tab.close")
+                                    .with_input("b".to_owned())
+                                    .enabled(false),
+                            ]
+                        });
+                        ctx.add("tabs", "".into(), tabs, 0);
+                    });
+                });
+            });
+        let world = app.world_mut();
+        world.resource_scope(|world, mut entrys: Mut<EditorMenuEntrys>| {
+            let mut path = vec![0, 2, 0];
+            let levels = levels(&mut entrys.0, &mut path, world);
+            assert_eq!(path, [0, 2, 0]);
+            assert_eq!(levels.iter().map(Vec::len).collect::<Vec<_>>(), [1, 3, 2]);
+            assert_eq!(levels[1][2].mnemonic, Some('c'));
+
+            activate_at(&mut entrys.0, &[0, 1], 1, world);
+            assert_eq!(*world.resource::<Gizmo>(), Gizmo::Rotate);
+            activate_at(&mut entrys.0, &path, 0, world);
+            activate_at(&mut entrys.0, &path, 1, world);
+            assert_eq!(world.resource::<Closed>().0, ["a"]);
+        });
+    }
+}
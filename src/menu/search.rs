@@ -0,0 +1,264 @@
+//! Searching the whole [`EditorMenuEntrys`](super::EditorMenuEntrys) tree, see [`menu_search_ui`].
+//!
+//! The menu bar is borrowed while its items are shown, so the results are computed
+//! and the chosen item is run by the bar itself, before its items are drawn.
+//! The results are only recomputed while the search field is shown, one frame late.
+
+use bevy::prelude::*;
+use egui::{Key, TextEdit, Ui};
+use rust_i18n::t;
+
+use super::{navigation::menu_label, ItemGroup, MenuItemVariant};
+use crate::utils::{fuzzy::fuzzy_score, identifier::Identifier};
+
+const MAX_RESULTS: usize = 20;
+
+/// An item of the menu tree matching [`MenuSearch::query`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuSearchResult {
+    /// The path of the item, usable with [`MenuItemProvider::find_subitem_recursive`](super::MenuItemProvider::find_subitem_recursive).
+    pub path: Identifier,
+    /// The names of the submenus leading to the item, e.g. `File › Export › PNG`.
+    pub breadcrumb: String,
+    /// Whether the availability condition of the button holds, always true for checkboxes and toggles.
+    pub enabled: bool,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct MenuSearch {
+    pub query: String,
+    results: Vec<MenuSearchResult>,
+    pending: Option<Identifier>,
+    /// Whether [`menu_search_ui`] was drawn since the results were last computed.
+    shown: bool,
+}
+
+impl MenuSearch {
+    /// The results for the query as of the last time the menu bar was shown, best first.
+    pub fn results(&self) -> &[MenuSearchResult] {
+        &self.results
+    }
+    /// Runs the item at `path` the next time the menu bar is shown.
+    pub fn run(&mut self, path: Identifier) {
+        self.pending = Some(path);
+    }
+}
+
+/// Returns the buttons, checkboxes and toggles of `group` whose breadcrumb or path matches `query`, best first.
+/// Radio groups, dynamic and custom items have no single action to run, so they are not searched.
+/// Hidden items and the content of hidden submenus are skipped.
+pub fn search_menu(group: &mut ItemGroup, query: &str, world: &mut World) -> Vec<MenuSearchResult> {
+    let mut results = Vec::new();
+    collect_results(
+        group,
+        query,
+        world,
+        &mut Identifier::from_iter(std::iter::empty::<String>()),
+        &mut Vec::new(),
+        &mut results,
+    );
+    results.sort_by(|(score1, result1), (score2, result2)| {
+        score2
+            .cmp(score1)
+            .then_with(|| result1.breadcrumb.cmp(&result2.breadcrumb))
+    });
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn collect_results(
+    group: &mut ItemGroup,
+    query: &str,
+    world: &mut World,
+    path: &mut Identifier,
+    breadcrumb: &mut Vec<String>,
+    results: &mut Vec<(i64, MenuSearchResult)>,
+) {
+    for (id, item) in group.items.iter_mut().filter(|(_, item)| !item.hidden) {
+        path.push(id.clone());
+        let label = menu_label(&item.name).into_owned();
+        let mut matched = |enabled: &mut dyn FnMut(&mut World) -> bool| {
+            let crumbs = breadcrumb
+                .iter()
+                .chain([&label])
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" › ");
+            let score = fuzzy_score(query, &crumbs).max(fuzzy_score(query, &path.to_string()));
+            if let Some(score) = score {
+                results.push((
+                    score,
+                    MenuSearchResult {
+                        path: path.clone(),
+                        breadcrumb: crumbs,
+                        enabled: enabled(world),
+                    },
+                ));
+            }
+        };
+        match &mut item.source {
+            MenuItemVariant::Button(button) => matched(&mut |world| button.is_available(world)),
+            MenuItemVariant::Checkbox(_) | MenuItemVariant::Toggle(_) => matched(&mut |_| true),
+            MenuItemVariant::SubMenu(sub) => {
+                breadcrumb.push(label);
+                collect_results(&mut sub.group, query, world, path, breadcrumb, results);
+                breadcrumb.pop();
+            }
+            MenuItemVariant::Category(category) => {
+                collect_results(&mut category.group, query, world, path, breadcrumb, results);
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
+
+/// Runs the button or toggle at `path`, or flips the checkbox there, respecting the availability of buttons.
+pub fn activate_path(group: &mut ItemGroup, path: &Identifier, world: &mut World) {
+    match group
+        .find_item_recursive(&path.to_string())
+        .map(|item| &mut item.source)
+    {
+        Some(MenuItemVariant::Button(button)) => button.activate(world),
+        Some(MenuItemVariant::Checkbox(checkbox)) => {
+            let checked = checkbox.is_checked(world);
+            checkbox.set(world, !checked);
+        }
+        Some(MenuItemVariant::Toggle(toggle)) => {
+            let action = toggle.action().clone();
+            super::run_menu_action(&action, world);
+        }
+        _ => {}
+    }
+}
+
+/// Runs the item chosen in [`menu_search_ui`] and refreshes the results if it was shown, called by the menu bar.
+pub(crate) fn update_search(bar: &mut ItemGroup, world: &mut World) {
+    let Some(mut search) = world.get_resource_mut::<MenuSearch>() else {
+        return;
+    };
+    let pending = search.pending.take();
+    if pending.is_some() {
+        search.query.clear();
+    }
+    let shown = std::mem::take(&mut search.shown);
+    let query = search.query.clone();
+    if let Some(path) = pending {
+        activate_path(bar, &path, world);
+    }
+    if !shown && !query.is_empty() {
+        return;
+    }
+    let results = if query.is_empty() {
+        Vec::new()
+    } else {
+        let mut results = search_menu(bar, &query, world);
+        results.truncate(MAX_RESULTS);
+        results
+    };
+    world.resource_mut::<MenuSearch>().results = results;
+}
+
+/// A search field listing the matching items of the menu bar, clicking one runs it.
+/// Meant to be added as a [`Custom`](super::Custom) item, e.g. the first one of a Help submenu.
+pub fn menu_search_ui(ui: &mut Ui, world: &mut World, name: &str) {
+    let Some(mut search) = world.get_resource_mut::<MenuSearch>() else {
+        return;
+    };
+    search.shown = true;
+    let hint = if name.is_empty() {
+        t!("menu.search_hint").into_owned()
+    } else {
+        name.to_owned()
+    };
+    let response = ui.add(TextEdit::singleline(&mut search.query).hint_text(hint));
+    let mut chosen = (response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)))
+        .then(|| search.results.iter().find(|result| result.enabled))
+        .flatten()
+        .map(|result| result.path.clone());
+    if !search.query.is_empty() && search.results.is_empty() {
+        ui.weak(t!("menu.search_no_result"));
+    }
+    for result in &search.results {
+        if ui
+            .add_enabled(result.enabled, egui::Button::new(&result.breadcrumb))
+            .clicked()
+        {
+            chosen = Some(result.path.clone());
+        }
+    }
+    if let Some(path) = chosen {
+        search.run(path);
+        ui.close_menu();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        action::ActionPlugin,
+        menu::{Button, Checkbox, EditorMenuEntrys, MenuExt, MenuPlugin},
+    };
+
+    #[derive(Resource, Default)]
+    struct Exported;
+
+    #[derive(Resource, Default)]
+    struct View {
+        grid: bool,
+    }
+
+    #[test]
+    fn test_search_menu() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, MenuPlugin))
+            .init_resource::<View>()
+            .menu_context(|mut ctx| {
+                ctx.with_sub_menu("file", "&File".into(), 0, |mut ctx| {
+                    ctx.with_sub_menu("export", "&Export".into(), 0, |mut ctx| {
+                        ctx.add(
+                            "png",
+                            "PNG".into(),
+                            Button::new_conditioned("file.export_png", resource_exists::<Exported>),
+                            0,
+                        );
+                    });
+                    ctx.add("save", "&Save".into(), Button::new("file.save"), 1);
+                    ctx.add("hidden", "Export hidden".into(), Button::new("x"), 2);
+                    ctx.set_hidden("hidden", true).unwrap();
+                    ctx.add(
+                        "grid",
+                        "Show &grid".into(),
+                        Checkbox::from_resource::<View>(|view| &mut view.grid),
+                        3,
+                    );
+                });
+            });
+        let world = app.world_mut();
+        world.resource_scope(|world, mut entrys: Mut<EditorMenuEntrys>| {
+            let results = search_menu(&mut entrys.0, "export", world);
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].breadcrumb, "File › Export › PNG");
+            assert_eq!(results[0].path, "file.export.png".into());
+            assert!(!results[0].enabled);
+
+            world.init_resource::<Exported>();
+            let results = search_menu(&mut entrys.0, "file.save", world);
+            assert_eq!(results[0].breadcrumb, "File › Save");
+            assert!(search_menu(&mut entrys.0, "fep", world)[0].enabled);
+
+            let results = search_menu(&mut entrys.0, "grid", world);
+            assert_eq!(results[0].breadcrumb, "File › Show grid");
+            activate_path(&mut entrys.0, &results[0].path, world);
+            assert!(world.resource::<View>().grid);
+
+            // The results are only refreshed once the search field has been drawn.
+            world.resource_mut::<MenuSearch>().query = "save".into();
+            update_search(&mut entrys.0, world);
+            assert!(world.resource::<MenuSearch>().results().is_empty());
+            world.resource_mut::<MenuSearch>().shown = true;
+            update_search(&mut entrys.0, world);
+            assert_eq!(world.resource::<MenuSearch>().results().len(), 1);
+        });
+    }
+}
@@ -1,20 +1,36 @@
+use std::fmt::Display;
+
 use bevy::{
     app::{Plugin, Update},
     ecs::{
         query::With,
         system::{Query, ResMut, Resource},
+        world::World,
     },
+    log::error,
     prelude::{Deref, DerefMut},
+    reflect::Reflect,
     window::PrimaryWindow,
 };
 use bevy_egui::EguiContext;
 use egui_notify::Toasts;
+use rust_i18n::t;
+
+use crate::action::{failure_message, ActionError, ActionId, ActionsExt};
+
+pub const CLEAR_NOTIFICATIONS: &str = "notifications.clear";
 
 pub struct NotificationPlugin;
 
 impl Plugin for NotificationPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_resource::<ToastsStorage>()
+            .init_resource::<UnreadErrors>()
+            .register_action(
+                CLEAR_NOTIFICATIONS,
+                "Dismiss all notifications",
+                clear_notifications,
+            )
             .add_systems(Update, show_egui_notifies);
     }
 }
@@ -32,6 +48,18 @@ impl Default for ToastsStorage {
     }
 }
 
+/// The number of error toasts, from [`notify_error`] and [`notify_action_failure`], shown since the last [`CLEAR_NOTIFICATIONS`].
+#[derive(Resource, Default, Debug)]
+pub struct UnreadErrors(pub usize);
+
+fn clear_notifications(
+    mut toasts: ResMut<ToastsStorage>,
+    mut unread: ResMut<UnreadErrors>,
+) {
+    toasts.dismiss_all_toasts();
+    unread.0 = 0;
+}
+
 fn show_egui_notifies(
     mut context: Query<&mut EguiContext, With<PrimaryWindow>>,
     mut toasts: ResMut<ToastsStorage>,
@@ -40,3 +68,43 @@ fn show_egui_notifies(
         toasts.show(ctx.get_mut())
     }
 }
+
+fn show_error(world: &mut World, text: String) {
+    if let Some(mut toasts) = world.get_resource_mut::<ToastsStorage>() {
+        toasts.error(text);
+    }
+    if let Some(mut unread) = world.get_resource_mut::<UnreadErrors>() {
+        unread.0 += 1;
+    }
+}
+
+/// Logs `message` and shows it as an error toast, for failures that are not caused by an action.
+pub fn notify_error(world: &mut World, message: impl Display) {
+    error!("{message}");
+    show_error(world, message.to_string());
+}
+
+/// Logs the failure of `action` and shows it as an error toast.
+pub fn notify_action_failure(world: &mut World, action: &ActionId, message: impl Display) {
+    error!("action {action} failed: {message}");
+    show_error(
+        world,
+        t!("action.failed", action = action, error = message).into_owned(),
+    );
+}
+
+/// Calls [`notify_action_failure`] if `action` could not be run or returned an `Err`.
+pub fn notify_action_result(
+    world: &mut World,
+    action: &ActionId,
+    result: &Result<Box<dyn Reflect>, ActionError>,
+) {
+    match result {
+        Ok(output) => {
+            if let Some(message) = failure_message(&**output) {
+                notify_action_failure(world, action, message);
+            }
+        }
+        Err(err) => notify_action_failure(world, action, err),
+    }
+}
@@ -0,0 +1,253 @@
+//! Command palette, a fuzzy searchable list of every action in [`ActionRegistry`].
+//! Opened with `Ctrl+Shift+P` (or the [`TOGGLE_PALETTE`] action).
+
+use std::any::TypeId;
+
+use bevy::{prelude::*, reflect::TypeInfo, window::PrimaryWindow};
+use bevy_egui::EguiContext;
+use egui::{Align2, Key, ScrollArea, TextEdit};
+use rust_i18n::t;
+
+use crate::{
+    action::{ActionError, ActionId, ActionRegistry, ActionsExt},
+    hotkeys::{Hotkey, HotkeyRegistry, HotkeysExt, InputKey, Modifier},
+    notifications::{notify_action_failure, notify_action_result},
+    utils::fuzzy::fuzzy_score,
+    widgets::ArgumentEditor,
+};
+
+pub const TOGGLE_PALETTE: &str = "palette.toggle";
+
+#[derive(Resource, Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+    /// Shown instead of the action list when the chosen action takes an input.
+    prompt: Option<ArgumentEditor>,
+}
+
+impl CommandPalette {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.prompt = None;
+    }
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.close()
+        } else {
+            self.open()
+        }
+    }
+}
+
+struct PaletteEntry {
+    id: ActionId,
+    description: String,
+    hotkeys: String,
+    input: &'static TypeInfo,
+}
+
+fn collect_entries(
+    query: &str,
+    actions: &ActionRegistry,
+    hotkeys: &HotkeyRegistry,
+) -> Vec<PaletteEntry> {
+    let mut entries: Vec<_> = actions
+        .iter()
+        .filter(|(id, _)| id.to_string() != TOGGLE_PALETTE)
+        .filter_map(|(id, storage)| {
+            let score = fuzzy_score(query, &id.to_string())
+                .max(fuzzy_score(query, storage.get_description()))?;
+            let hotkeys = hotkeys
+                .get(id)
+                .map(|list| {
+                    list.iter()
+                        .filter(|hotkey| !hotkey.binding.is_empty())
+                        .map(Hotkey::hotkey_text)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            Some((
+                score,
+                PaletteEntry {
+                    id: id.clone(),
+                    description: storage.get_description().to_owned(),
+                    hotkeys,
+                    input: storage.input_type_info(),
+                },
+            ))
+        })
+        .collect();
+    entries.sort_by(|(score1, entry1), (score2, entry2)| {
+        score2
+            .cmp(score1)
+            .then_with(|| entry1.id.to_string().cmp(&entry2.id.to_string()))
+    });
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Draws the palette, returning the action to run together with its input.
+fn palette_ui(
+    ctx: &egui::Context,
+    world: &World,
+    palette: &mut CommandPalette,
+) -> Option<(ActionId, Box<dyn Reflect>)> {
+    let mut to_run = None;
+    let mut open = palette.open;
+    egui::Window::new("command_palette")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0.0, 48.0])
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            let actions = world.resource::<ActionRegistry>();
+            if let Some(editor) = &mut palette.prompt {
+                let type_registry = world.resource::<AppTypeRegistry>().read();
+                let ty = actions
+                    .get(editor.action())
+                    .map_or("", |storage| storage.input_type_info().type_path());
+                if editor.value().is_none() {
+                    ui.weak(t!("palette.unsupported_argument", ty = ty));
+                } else {
+                    ui.label(t!("palette.argument", action = editor.action(), ty = ty));
+                    if let Some(input) = editor.ui(ui, &type_registry) {
+                        to_run = Some((editor.action().clone(), input));
+                        open = false;
+                    }
+                }
+                if ui.input(|i| i.key_pressed(Key::Escape)) {
+                    palette.prompt = None;
+                }
+                return;
+            }
+
+            let response = ui.add(
+                TextEdit::singleline(&mut palette.query)
+                    .hint_text(t!("palette.hint"))
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                palette.selected = 0;
+            }
+            let entries =
+                collect_entries(&palette.query, actions, world.resource::<HotkeyRegistry>());
+            if entries.is_empty() {
+                ui.weak(t!("palette.no_result"));
+            }
+            ui.input(|i| {
+                if i.key_pressed(Key::ArrowDown) {
+                    palette.selected = (palette.selected + 1).min(entries.len().saturating_sub(1));
+                }
+                if i.key_pressed(Key::ArrowUp) {
+                    palette.selected = palette.selected.saturating_sub(1);
+                }
+                if i.key_pressed(Key::Escape) {
+                    open = false;
+                }
+            });
+            let mut chosen = ui
+                .input(|i| i.key_pressed(Key::Enter))
+                .then_some(palette.selected)
+                .filter(|i| *i < entries.len());
+            ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for (i, entry) in entries.iter().enumerate() {
+                    let text = if entry.description.is_empty() {
+                        entry.id.to_string()
+                    } else {
+                        format!("{} ({})", entry.description, entry.id)
+                    };
+                    let response = ui.add(
+                        egui::Button::new(text)
+                            .shortcut_text(&entry.hotkeys)
+                            .selected(i == palette.selected)
+                            .frame(false)
+                            .min_size([ui.available_width(), 0.0].into()),
+                    );
+                    if i == palette.selected {
+                        response.scroll_to_me(None);
+                    }
+                    if response.clicked() {
+                        chosen = Some(i);
+                    }
+                }
+            });
+            if let Some(entry) = chosen.map(|i| &entries[i]) {
+                if entry.input.type_id() == TypeId::of::<()>() {
+                    to_run = Some((entry.id.clone(), Box::new(()) as Box<dyn Reflect>));
+                    open = false;
+                } else {
+                    palette.prompt = ArgumentEditor::new(
+                        entry.id.clone(),
+                        actions,
+                        &world.resource::<AppTypeRegistry>().read(),
+                    );
+                }
+            }
+        });
+    palette.open = open;
+    to_run
+}
+
+fn show_command_palette(world: &mut World) {
+    if !world.resource::<CommandPalette>().is_open() {
+        return;
+    }
+    let Ok(mut egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let ctx = egui_context.get_mut().clone();
+    let to_run = world.resource_scope(|world: &mut World, mut palette: Mut<CommandPalette>| {
+        palette_ui(&ctx, world, &mut palette)
+    });
+    if let Some((id, input)) = to_run {
+        world.resource_scope(|world: &mut World, mut actions: Mut<ActionRegistry>| {
+            match actions.run_instant_dyn(&id, input, world) {
+                Err(ActionError::MismatchInput {
+                    expected_type_name, ..
+                }) => notify_action_failure(
+                    world,
+                    &id,
+                    t!("palette.invalid_argument", ty = expected_type_name),
+                ),
+                result => notify_action_result(world, &id, &result),
+            }
+        });
+    }
+}
+
+fn toggle_palette(mut palette: ResMut<CommandPalette>) {
+    palette.toggle();
+}
+
+pub struct CommandPalettePlugin;
+
+impl Plugin for CommandPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandPalette>()
+            .register_action(TOGGLE_PALETTE, "Show all actions", toggle_palette)
+            .register_hotkey(
+                TOGGLE_PALETTE,
+                [Hotkey::new_global([
+                    InputKey::from(Modifier::CmdOrCtrl),
+                    Modifier::Shift.into(),
+                    KeyCode::KeyP.into(),
+                ])],
+            )
+            .add_systems(Update, show_command_palette);
+    }
+}
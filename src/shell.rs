@@ -0,0 +1,361 @@
+//! A root UI for editors: the menu bar, the [`status`] bar, side and bottom panels and the main dock.
+//!
+//! Add [`EditorShellPlugin`] instead of drawing those in an own system, and inject panels with [`EditorShellExt`].
+//! Everything else drawn on the primary window in [`Update`] goes around or above the shell, as usual with egui panels,
+//! order such systems relative to [`EditorShellSet`].
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::EguiContext;
+use egui::{CentralPanel, Color32, Frame, SidePanel, TopBottomPanel, Ui};
+use egui_dock::{DockArea, Style};
+use indexmap::IndexMap;
+
+use self::status::{status_bar_ui, StatusBarExt, StatusSegment};
+use crate::{
+    hotkeys::chord::PendingChordPopupSet,
+    menu::show_menu_ui,
+    tab_system::{
+        areas::{pressed_inside, show_dock_area, DockAreaExt, DockAreaId, DockAreas},
+        HeDockState, HeTabViewer, TabId, TabRegistry,
+    },
+    utils::identifier::Identifier,
+};
+
+pub mod status;
+
+/// The set [`show_editor_shell`] runs in, in [`Update`].
+///
+/// Panels of systems running before it take their space from the outside of the shell,
+/// systems running after it can only add windows and areas, as the main dock already took the remaining space.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct EditorShellSet;
+
+pub type ShellUiSystem = Box<dyn System<In = &'static mut Ui, Out = ()>>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PanelSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+pub enum PanelContent {
+    /// A widget system, like the ones shown with [`widget`](crate::widgets::widget).
+    Widget(ShellUiSystem),
+    /// A dock area of the primary window, see [`DockAreas`](crate::tab_system::areas::DockAreas).
+    DockArea(DockAreaId),
+}
+
+pub struct ShellPanel {
+    pub side: PanelSide,
+    pub content: PanelContent,
+    pub visible: bool,
+}
+
+#[derive(Resource)]
+pub struct EditorShell {
+    pub menu_bar: bool,
+    pub status_bar: bool,
+    /// The style of the main dock, derived from the egui style if `None`.
+    pub dock_style: Option<Style>,
+    /// Shown in insertion order, so earlier panels take the outer space.
+    panels: IndexMap<Identifier, ShellPanel>,
+}
+
+impl Default for EditorShell {
+    fn default() -> Self {
+        Self {
+            menu_bar: true,
+            status_bar: true,
+            dock_style: None,
+            panels: IndexMap::new(),
+        }
+    }
+}
+
+impl EditorShell {
+    pub fn panel(&self, id: &Identifier) -> Option<&ShellPanel> {
+        self.panels.get(id)
+    }
+    pub fn panel_mut(&mut self, id: &Identifier) -> Option<&mut ShellPanel> {
+        self.panels.get_mut(id)
+    }
+    /// Does nothing if the panel does not exist.
+    pub fn set_panel_visible(&mut self, id: &Identifier, visible: bool) {
+        if let Some(panel) = self.panels.get_mut(id) {
+            panel.visible = visible;
+        }
+    }
+}
+
+pub trait EditorShellExt {
+    fn add_shell_panel<M>(
+        &mut self,
+        id: impl Into<Identifier>,
+        side: PanelSide,
+        system: impl IntoSystem<&'static mut Ui, (), M>,
+    ) -> &mut Self;
+    /// Adds a panel showing a new dock area with the same id.
+    fn add_shell_dock_panel(
+        &mut self,
+        id: impl Into<Identifier>,
+        side: PanelSide,
+        tabs: Vec<TabId>,
+    ) -> &mut Self;
+    /// Adds a widget system to the status bar, after the existing left aligned segments.
+    /// See [`StatusBarExt::add_status_segment`] for alignment, priority and actions.
+    fn add_status_item<M>(
+        &mut self,
+        id: impl Into<Identifier>,
+        system: impl IntoSystem<&'static mut Ui, (), M>,
+    ) -> &mut Self;
+}
+
+fn init_ui_system<M>(
+    world: &mut World,
+    system: impl IntoSystem<&'static mut Ui, (), M>,
+) -> ShellUiSystem {
+    let mut system = IntoSystem::into_system(system);
+    system.initialize(world);
+    Box::new(system)
+}
+
+impl EditorShellExt for App {
+    fn add_shell_panel<M>(
+        &mut self,
+        id: impl Into<Identifier>,
+        side: PanelSide,
+        system: impl IntoSystem<&'static mut Ui, (), M>,
+    ) -> &mut Self {
+        let content = PanelContent::Widget(init_ui_system(self.world_mut(), system));
+        self.world_mut()
+            .resource_mut::<EditorShell>()
+            .panels
+            .insert(
+                id.into(),
+                ShellPanel {
+                    side,
+                    content,
+                    visible: true,
+                },
+            );
+        self
+    }
+    fn add_shell_dock_panel(
+        &mut self,
+        id: impl Into<Identifier>,
+        side: PanelSide,
+        tabs: Vec<TabId>,
+    ) -> &mut Self {
+        let id = id.into();
+        self.add_dock_area(id.clone(), tabs);
+        self.world_mut()
+            .resource_mut::<EditorShell>()
+            .panels
+            .insert(
+                id.clone(),
+                ShellPanel {
+                    side,
+                    content: PanelContent::DockArea(id),
+                    visible: true,
+                },
+            );
+        self
+    }
+    fn add_status_item<M>(
+        &mut self,
+        id: impl Into<Identifier>,
+        system: impl IntoSystem<&'static mut Ui, (), M>,
+    ) -> &mut Self {
+        let mut system = init_ui_system(self.world_mut(), system);
+        self.add_status_segment(
+            id,
+            StatusSegment::new(move |In(mut ui): In<Ui>, world: &mut World| {
+                run_ui_system(&mut system, &mut ui, world)
+            }),
+        )
+    }
+}
+
+fn run_ui_system(system: &mut ShellUiSystem, ui: &mut Ui, world: &mut World) {
+    system.run(unsafe { &mut *(ui as *mut Ui) }, world);
+    system.apply_deferred(world);
+}
+
+fn panel_ui(id: &Identifier, panel: &mut ShellPanel, ctx: &egui::Context, world: &mut World) {
+    let add_contents = |ui: &mut Ui| match &mut panel.content {
+        PanelContent::Widget(system) => run_ui_system(system, ui, world),
+        PanelContent::DockArea(area) => show_dock_area(ui, world, area),
+    };
+    let panel_id = format!("shell_panel_{id}");
+    match panel.side {
+        PanelSide::Left => SidePanel::left(panel_id).show(ctx, add_contents),
+        PanelSide::Right => SidePanel::right(panel_id).show(ctx, add_contents),
+        PanelSide::Top => TopBottomPanel::top(panel_id)
+            .resizable(true)
+            .show(ctx, add_contents),
+        PanelSide::Bottom => TopBottomPanel::bottom(panel_id)
+            .resizable(true)
+            .show(ctx, add_contents),
+    };
+}
+
+/// Draws the shell on the primary window, runs in [`EditorShellSet`].
+pub fn show_editor_shell(world: &mut World) {
+    let Ok(mut egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let ctx = egui_context.get_mut().clone();
+    world.resource_scope(|world, mut shell: Mut<EditorShell>| {
+        if shell.menu_bar {
+            TopBottomPanel::top("shell_menu_bar").show(&ctx, |ui| {
+                ui.horizontal(|ui| show_menu_ui(ui, world));
+            });
+        }
+        if shell.status_bar {
+            TopBottomPanel::bottom("shell_status_bar").show(&ctx, |ui| status_bar_ui(ui, world));
+        }
+        for (id, panel) in shell.panels.iter_mut().filter(|(_, panel)| panel.visible) {
+            panel_ui(id, panel, &ctx, world);
+        }
+        let style = shell
+            .dock_style
+            .clone()
+            .unwrap_or_else(|| Style::from_egui(&ctx.style()));
+        if !world.contains_resource::<HeDockState>() {
+            CentralPanel::default().show(&ctx, |_| {});
+            return;
+        }
+        // The frame DockArea::show uses, shown here to know the rect of the main dock.
+        let frame = Frame::central_panel(&ctx.style())
+            .inner_margin(0.)
+            .fill(Color32::TRANSPARENT);
+        CentralPanel::default().frame(frame).show(&ctx, |ui| {
+            let rect = ui.max_rect();
+            world.resource_scope(|world, mut registry: Mut<TabRegistry>| {
+                world.resource_scope(|world, mut state: Mut<HeDockState>| {
+                    DockArea::new(&mut state.0).style(style).show_inside(
+                        ui,
+                        &mut HeTabViewer {
+                            world,
+                            registry: &mut registry,
+                        },
+                    );
+                });
+            });
+            if pressed_inside(ui, rect) {
+                if let Some(mut areas) = world.get_resource_mut::<DockAreas>() {
+                    areas.set_focused(None);
+                }
+            }
+        });
+    });
+}
+
+/// Draws [`EditorShell`] on the primary window, needs [`HeliumFramework`](crate::HeliumFramework) and `EguiPlugin`.
+pub struct EditorShellPlugin;
+
+impl Plugin for EditorShellPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorShell>()
+            .add_systems(Update, show_editor_shell.in_set(EditorShellSet))
+            .configure_sets(
+                Update,
+                PendingChordPopupSet.run_if(not(status::status_bar_shown)),
+            );
+        status::build_status_bar(app);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use self::status::{StatusAlignment, StatusBar};
+    use super::*;
+    use crate::{
+        menu::MenuPlugin,
+        tab_system::{areas::DockAreas, TabPlugin, TabRegistrationExt},
+    };
+
+    #[derive(Resource, Default)]
+    struct Drawn(Vec<&'static str>);
+
+    #[test]
+    fn test_shell_panels() {
+        let mut app = App::new();
+        app.add_plugins((TabPlugin, EditorShellPlugin))
+            .add_shell_panel("outline", PanelSide::Left, |In(ui): In<&'static mut Ui>| {
+                ui.label("outline");
+            })
+            .add_shell_dock_panel("bottom", PanelSide::Bottom, vec!["log".into()])
+            .add_status_item("fps", |In(ui): In<&'static mut Ui>| {
+                ui.label("fps");
+            });
+        let world = app.world_mut();
+        assert_eq!(
+            world.resource::<DockAreas>().find_tab(&"log".into()),
+            Some(&"bottom".into())
+        );
+        let mut shell = world.resource_mut::<EditorShell>();
+        shell.set_panel_visible(&"outline".into(), false);
+        assert!(!shell.panel(&"outline".into()).unwrap().visible);
+        assert!(matches!(
+            shell.panel(&"bottom".into()).unwrap().content,
+            PanelContent::DockArea(_)
+        ));
+        let bar = world.resource::<StatusBar>();
+        assert_eq!(
+            bar.ids(StatusAlignment::Left).last(),
+            Some(&Identifier::from("fps"))
+        );
+    }
+
+    #[test]
+    fn test_show_editor_shell() {
+        let mut app = App::new();
+        app.add_plugins((TabPlugin, MenuPlugin, EditorShellPlugin))
+            .init_resource::<Drawn>()
+            .insert_resource(HeDockState(egui_dock::DockState::new(vec!["a".into()])))
+            .register_tab(
+                "a",
+                "A",
+                |In(_): In<Ui>, mut drawn: ResMut<Drawn>| drawn.0.push("tab"),
+                || true,
+            )
+            .add_shell_panel(
+                "outline",
+                PanelSide::Left,
+                |In(_): In<&'static mut Ui>, mut drawn: ResMut<Drawn>| drawn.0.push("panel"),
+            )
+            .add_shell_panel(
+                "hidden",
+                PanelSide::Right,
+                |In(_): In<&'static mut Ui>, mut drawn: ResMut<Drawn>| drawn.0.push("hidden"),
+            )
+            .add_status_item(
+                "status",
+                |In(_): In<&'static mut Ui>, mut drawn: ResMut<Drawn>| drawn.0.push("status"),
+            );
+        app.world_mut()
+            .resource_mut::<EditorShell>()
+            .set_panel_visible(&"hidden".into(), false);
+        let ctx = app
+            .world_mut()
+            .spawn((EguiContext::default(), PrimaryWindow))
+            .get_mut::<EguiContext>()
+            .unwrap()
+            .get_mut()
+            .clone();
+
+        ctx.begin_pass(egui::RawInput::default());
+        app.update();
+        let _ = ctx.end_pass();
+        assert_eq!(
+            app.world().resource::<Drawn>().0,
+            ["status", "panel", "tab"]
+        );
+    }
+}
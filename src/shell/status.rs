@@ -0,0 +1,316 @@
+//! The status bar of the [`EditorShell`](super::EditorShell), made of segments contributed by plugins.
+//!
+//! A segment is a system taking `In<Ui>`, like a tab. Left segments are laid out from the left edge
+//! and right ones from the right edge, lower priorities first.
+
+use std::borrow::Cow;
+
+use bevy::{
+    ecs::schedule::{BoxedCondition, Condition},
+    prelude::*,
+};
+use egui::{Align, Layout, Sense, Ui, UiBuilder};
+use indexmap::IndexMap;
+use rust_i18n::t;
+
+use super::EditorShell;
+use crate::{
+    action::{ActionId, ActionRegistry},
+    hotkeys::chord::ChordState,
+    notifications::{notify_action_result, UnreadErrors, CLEAR_NOTIFICATIONS},
+    tab_system::{FocusedTab, TabRegistry},
+    utils::{identifier::Identifier, new_condition},
+};
+
+pub const PENDING_CHORD_SEGMENT: &str = "hotkeys.pending_chord";
+pub const FOCUSED_TAB_SEGMENT: &str = "tab.focused";
+pub const NOTIFICATIONS_SEGMENT: &str = "notifications.unread";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StatusAlignment {
+    #[default]
+    Left,
+    Right,
+}
+
+pub struct StatusSegment {
+    system: Box<dyn System<In = Ui, Out = ()>>,
+    shown_when: BoxedCondition,
+    alignment: StatusAlignment,
+    priority: usize,
+    action: Option<ActionId>,
+    tooltip: Option<Box<dyn Fn() -> Cow<'static, str> + Send + Sync>>,
+}
+
+impl StatusSegment {
+    /// A left aligned segment of priority 0, always shown.
+    pub fn new<M>(system: impl IntoSystem<Ui, (), M>) -> Self {
+        Self {
+            system: Box::new(IntoSystem::into_system(system)),
+            shown_when: new_condition(|| true),
+            alignment: StatusAlignment::Left,
+            priority: 0,
+            action: None,
+            tooltip: None,
+        }
+    }
+    pub fn aligned(mut self, alignment: StatusAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+    pub fn with_priority(mut self, priority: usize) -> Self {
+        self.priority = priority;
+        self
+    }
+    /// Runs `action` without input when the segment is clicked.
+    pub fn with_action(mut self, action: impl Into<ActionId>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+    pub fn with_tooltip(self, tooltip: impl Into<Cow<'static, str>>) -> Self {
+        let tooltip = tooltip.into();
+        self.with_tooltip_fn(move || tooltip.clone())
+    }
+    /// Computes the tooltip each time it is shown, e.g. to translate it into the current locale.
+    pub fn with_tooltip_fn(
+        mut self,
+        tooltip: impl Fn() -> Cow<'static, str> + Send + Sync + 'static,
+    ) -> Self {
+        self.tooltip = Some(Box::new(tooltip));
+        self
+    }
+    /// Hides the segment while `condition` does not hold.
+    pub fn shown_when<M>(mut self, condition: impl Condition<M>) -> Self {
+        self.shown_when = new_condition(condition);
+        self
+    }
+    pub fn alignment(&self) -> StatusAlignment {
+        self.alignment
+    }
+    pub fn priority(&self) -> usize {
+        self.priority
+    }
+    pub fn is_shown(&mut self, world: &mut World) -> bool {
+        self.shown_when.run_readonly((), world)
+    }
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world);
+        self.shown_when.initialize(world);
+    }
+    fn ui(&mut self, ui: &mut Ui, world: &mut World, id: &Identifier) {
+        if !self.is_shown(world) {
+            return;
+        }
+        let sense = if self.action.is_some() {
+            Sense::click()
+        } else {
+            Sense::hover()
+        };
+        let child = ui.new_child(
+            UiBuilder::new()
+                .id_salt(("status_segment", id.to_string()))
+                .max_rect(ui.available_rect_before_wrap())
+                .layout(*ui.layout())
+                .sense(sense),
+        );
+        let child_id = child.unique_id();
+        // Based on the rect of the previous frame, the one of this frame is known once the system is done with the child.
+        let mut response = child.response();
+        self.system.run(child, world);
+        self.system.apply_deferred(world);
+        if let Some(drawn) = ui.ctx().read_response(child_id) {
+            ui.advance_cursor_after_rect(drawn.rect);
+        }
+        if let Some(tooltip) = &self.tooltip {
+            response = response.on_hover_text(tooltip());
+        }
+        if let (true, Some(action)) = (response.clicked(), &self.action) {
+            world.resource_scope(|world, mut actions: Mut<ActionRegistry>| {
+                let result = actions.run_instant(action, (), world);
+                notify_action_result(world, action, &result);
+            });
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct StatusBar {
+    segments: IndexMap<Identifier, StatusSegment>,
+}
+
+impl StatusBar {
+    pub fn get(&self, id: &Identifier) -> Option<&StatusSegment> {
+        self.segments.get(id)
+    }
+    pub fn remove(&mut self, id: &Identifier) -> Option<StatusSegment> {
+        self.segments.shift_remove(id)
+    }
+    /// The ids of the segments with `alignment`, in the order they are laid out.
+    pub fn ids(&self, alignment: StatusAlignment) -> impl Iterator<Item = &Identifier> {
+        self.segments
+            .iter()
+            .filter(move |(_, segment)| segment.alignment == alignment)
+            .map(|(id, _)| id)
+    }
+    /// Stable, so segments of the same priority keep the order they were added in.
+    fn sort(&mut self) {
+        self.segments
+            .sort_by(|_, segment1, _, segment2| segment1.priority.cmp(&segment2.priority));
+    }
+}
+
+pub trait StatusBarExt {
+    /// Adds a segment to the status bar, replacing the one with the same id.
+    fn add_status_segment(
+        &mut self,
+        id: impl Into<Identifier>,
+        segment: StatusSegment,
+    ) -> &mut Self;
+}
+
+impl StatusBarExt for App {
+    fn add_status_segment(
+        &mut self,
+        id: impl Into<Identifier>,
+        mut segment: StatusSegment,
+    ) -> &mut Self {
+        segment.initialize(self.world_mut());
+        let mut bar = self.world_mut().resource_mut::<StatusBar>();
+        bar.segments.insert(id.into(), segment);
+        bar.sort();
+        self
+    }
+}
+
+/// Shows the segments of the [`StatusBar`], called by the shell inside its bottom panel.
+pub fn status_bar_ui(ui: &mut Ui, world: &mut World) {
+    world.resource_scope(|world, mut bar: Mut<StatusBar>| {
+        ui.horizontal(|ui| {
+            for (id, segment) in bar
+                .segments
+                .iter_mut()
+                .filter(|(_, segment)| segment.alignment == StatusAlignment::Left)
+            {
+                segment.ui(ui, world, id);
+            }
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                for (id, segment) in bar
+                    .segments
+                    .iter_mut()
+                    .filter(|(_, segment)| segment.alignment == StatusAlignment::Right)
+                {
+                    segment.ui(ui, world, id);
+                }
+            });
+        });
+    });
+}
+
+/// Whether the shell shows a status bar, the pending chord is only shown as a popup otherwise.
+pub fn status_bar_shown(shell: Option<Res<EditorShell>>) -> bool {
+    shell.is_some_and(|shell| shell.status_bar)
+}
+
+fn pending_chord(In(mut ui): In<Ui>, state: Option<Res<ChordState>>) {
+    if let Some(text) = state.and_then(|state| state.pending_text()) {
+        ui.label(t!("hotkey.chord_pending", keys = text));
+    }
+}
+
+fn focused_tab(In(mut ui): In<Ui>, focused: Res<FocusedTab>, registry: Option<Res<TabRegistry>>) {
+    if let Some(tab) = &focused.0 {
+        let title = registry
+            .and_then(|registry| registry.get(tab).map(|tab| tab.title()))
+            .unwrap_or_else(|| tab.to_string().into());
+        ui.label(title);
+    }
+}
+
+fn unread_errors(In(mut ui): In<Ui>, unread: Res<UnreadErrors>) {
+    ui.label(t!("status.errors", count = unread.0));
+}
+
+pub(crate) fn build_status_bar(app: &mut App) {
+    app.init_resource::<StatusBar>()
+        .add_status_segment(
+            PENDING_CHORD_SEGMENT,
+            StatusSegment::new(pending_chord).shown_when(|state: Option<Res<ChordState>>| {
+                state.is_some_and(|state| state.is_pending())
+            }),
+        )
+        .add_status_segment(
+            FOCUSED_TAB_SEGMENT,
+            StatusSegment::new(focused_tab)
+                .aligned(StatusAlignment::Right)
+                .with_priority(10)
+                .shown_when(|focused: Option<Res<FocusedTab>>| {
+                    focused.is_some_and(|focused| focused.0.is_some())
+                }),
+        )
+        .add_status_segment(
+            NOTIFICATIONS_SEGMENT,
+            StatusSegment::new(unread_errors)
+                .aligned(StatusAlignment::Right)
+                .with_action(CLEAR_NOTIFICATIONS)
+                .with_tooltip_fn(|| t!("status.clear_notifications"))
+                .shown_when(|unread: Option<Res<UnreadErrors>>| {
+                    unread.is_some_and(|unread| unread.0 > 0)
+                }),
+        );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Connected(bool);
+
+    #[test]
+    fn test_segment_order() {
+        let mut app = App::new();
+        app.init_resource::<Connected>();
+        build_status_bar(&mut app);
+        app.add_status_segment(
+            "connection",
+            StatusSegment::new(|In(mut ui): In<Ui>| {
+                ui.label("connected");
+            })
+            .aligned(StatusAlignment::Right)
+            .shown_when(|connected: Res<Connected>| connected.0),
+        )
+        .add_status_segment(
+            "mode",
+            StatusSegment::new(|In(_): In<Ui>| {}).with_priority(1),
+        );
+
+        let world = app.world_mut();
+        world.resource_scope(|world, mut bar: Mut<StatusBar>| {
+            let ids = |bar: &StatusBar, alignment| bar.ids(alignment).cloned().collect::<Vec<_>>();
+            assert_eq!(
+                ids(&bar, StatusAlignment::Left),
+                [PENDING_CHORD_SEGMENT.into(), "mode".into()]
+            );
+            assert_eq!(
+                ids(&bar, StatusAlignment::Right),
+                [
+                    NOTIFICATIONS_SEGMENT.into(),
+                    "connection".into(),
+                    FOCUSED_TAB_SEGMENT.into()
+                ]
+            );
+            let connection = bar
+                .segments
+                .get_mut(&Identifier::from("connection"))
+                .unwrap();
+            assert!(!connection.is_shown(world));
+            world.resource_mut::<Connected>().0 = true;
+            assert!(connection.is_shown(world));
+            assert!(!bar
+                .segments
+                .get_mut(&Identifier::from(PENDING_CHORD_SEGMENT))
+                .unwrap()
+                .is_shown(world));
+        });
+    }
+}
@@ -1,12 +1,19 @@
 use std::borrow::Cow;
 
 use bevy::{ecs::schedule::BoxedCondition, prelude::*, utils::HashMap};
-use egui::{Ui, UiBuilder};
-use egui_dock::{DockState, TabViewer};
+use egui::{Sense, Ui, UiBuilder};
+use egui_dock::{DockState, NodeIndex, SurfaceIndex, TabViewer};
 use rust_i18n::t;
 use snafu::Snafu;
 
-use crate::utils::{identifier::Identifier, new_condition};
+use self::areas::DockAreas;
+
+use crate::{
+    menu::context::{context_menu_ui, show_context_menu, ContextMenuId, ContextTarget},
+    utils::{identifier::Identifier, new_condition},
+};
+
+pub mod areas;
 
 pub struct HeTabViewer<'a> {
     pub world: &'a mut World,
@@ -23,8 +30,30 @@ impl<'a> TabViewer for HeTabViewer<'a> {
             .into()
     }
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+        // Added before the content, so only clicks that no widget of the tab takes reach it.
+        let background = ui.interact(ui.max_rect(), ui.id().with("context_menu"), Sense::click());
+        show_context_menu(
+            &background,
+            self.world,
+            &ContextMenuId::Tab(tab.clone()),
+            ContextTarget::Tab(tab.clone()),
+        );
         self.registry.tab_ui(ui, self.world, tab);
     }
+    fn context_menu(
+        &mut self,
+        ui: &mut Ui,
+        tab: &mut Self::Tab,
+        _surface: SurfaceIndex,
+        _node: NodeIndex,
+    ) {
+        context_menu_ui(
+            ui,
+            self.world,
+            &ContextMenuId::TabHeader,
+            ContextTarget::Tab(tab.clone()),
+        );
+    }
 }
 
 #[derive(Debug, Resource)]
@@ -38,17 +67,54 @@ pub struct TabStorage {
     tab_title: Cow<'static, str>,
 }
 
-#[derive(Resource, Default, PartialEq, Eq)]
+/// The active tab of the focused dock node, updated by [`update_focused_tab`].
+#[derive(Resource, Default, PartialEq, Eq, Debug)]
 pub struct FocusedTab(pub Option<TabId>);
 
+/// Copies the focused tab of the dock pressed last, see [`DockAreas::focused`], into [`FocusedTab`].
+/// Falls back to [`HeDockState`], then to the first of the [`DockAreas`] with a focused tab.
+/// Runs in [`PostUpdate`] after the docks are shown.
+pub fn update_focused_tab(
+    state: Option<ResMut<HeDockState>>,
+    mut areas: Option<ResMut<DockAreas>>,
+    mut focused: ResMut<FocusedTab>,
+) {
+    let mut areas = areas.as_mut().map(|areas| areas.bypass_change_detection());
+    let from_area = areas.as_deref_mut().and_then(|areas| {
+        let id = areas.focused()?.clone();
+        areas
+            .get_mut(&id)?
+            .state
+            .find_active_focused()
+            .map(|(_, tab)| tab.clone())
+    });
+    let tab = from_area
+        .or_else(|| {
+            state?
+                .bypass_change_detection()
+                .0
+                .find_active_focused()
+                .map(|(_, tab)| tab.clone())
+        })
+        .or_else(|| {
+            areas?
+                .iter_mut()
+                .find_map(|(_, area)| area.state.find_active_focused().map(|(_, tab)| tab.clone()))
+        });
+    focused.set_if_neq(FocusedTab(tab));
+}
+
 pub fn tab_focused(tab: impl Into<TabId>) -> impl Condition<()> {
     resource_exists_and_equals(FocusedTab(Some(tab.into()))).and_then(|| true)
 }
 
 pub fn tab_opened(tab: impl Into<TabId>) -> impl Condition<()> {
     let tab = tab.into();
-    (move |res: Option<Res<HeDockState>>| res.is_some_and(|res| res.0.find_tab(&tab).is_some()))
-        .and_then(|| true)
+    (move |res: Option<Res<HeDockState>>, areas: Option<Res<DockAreas>>| {
+        res.is_some_and(|res| res.0.find_tab(&tab).is_some())
+            || areas.is_some_and(|areas| areas.find_tab(&tab).is_some())
+    })
+    .and_then(|| true)
 }
 
 impl TabStorage {
@@ -151,6 +217,15 @@ pub struct TabPlugin;
 impl Plugin for TabPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<TabRegistry>()
-            .init_resource::<FocusedTab>();
+            .init_resource::<FocusedTab>()
+            .init_resource::<DockAreas>()
+            .add_systems(Update, areas::show_window_docks)
+            .add_systems(
+                PostUpdate,
+                (
+                    update_focused_tab,
+                    (areas::tear_off_floating, areas::return_closed_windows).chain(),
+                ),
+            );
     }
 }
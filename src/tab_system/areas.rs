@@ -0,0 +1,421 @@
+//! Dock areas besides the main [`HeDockState`].
+//!
+//! An area of the primary window is shown where the app wants it with [`show_dock_area`], e.g. inside a bottom panel.
+//! An area bound to another window fills that window and is shown by [`show_window_docks`].
+//! Dragging a tab out of the main dock tears it off into a new window, closing that window moves its tabs back.
+//! Floating windows that appear otherwise, added by the app or part of a restored layout, stay in the primary window.
+//! Saved layouts include the tabs of those windows, see [`DockAreas::with_window_tabs`].
+//! Each dock keeps its own focused node, [`DockAreas::focused`] tells which dock was pressed last.
+
+use bevy::{prelude::*, utils::HashSet, window::PrimaryWindow};
+use bevy_egui::EguiContext;
+use egui::{Id, Rect, Ui};
+use egui_dock::{DockArea, DockState, SurfaceIndex};
+use indexmap::IndexMap;
+
+use super::{HeDockState, HeTabViewer, TabId, TabRegistry};
+use crate::utils::identifier::Identifier;
+
+pub type DockAreaId = Identifier;
+
+#[derive(Debug)]
+pub struct DockAreaState {
+    pub state: DockState<TabId>,
+    /// The window the area fills, `None` for an area of the primary window.
+    pub window: Option<Entity>,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct DockAreas {
+    areas: IndexMap<DockAreaId, DockAreaState>,
+    /// The floating surfaces of the main dock as of the last [`tear_off_floating`].
+    floating: HashSet<SurfaceIndex>,
+    focused: Option<DockAreaId>,
+}
+
+impl DockAreas {
+    pub fn insert(&mut self, id: impl Into<DockAreaId>, area: DockAreaState) {
+        self.areas.insert(id.into(), area);
+    }
+    pub fn get(&self, id: &DockAreaId) -> Option<&DockAreaState> {
+        self.areas.get(id)
+    }
+    pub fn get_mut(&mut self, id: &DockAreaId) -> Option<&mut DockAreaState> {
+        self.areas.get_mut(id)
+    }
+    pub fn remove(&mut self, id: &DockAreaId) -> Option<DockAreaState> {
+        if self.focused.as_ref() == Some(id) {
+            self.focused = None;
+        }
+        self.areas.shift_remove(id)
+    }
+    /// The area pressed last, `None` if the main dock was pressed after every area.
+    pub fn focused(&self) -> Option<&DockAreaId> {
+        self.focused.as_ref()
+    }
+    /// Called when a dock is pressed, with `None` for the main dock.
+    /// The shell does so for the docks it shows, an app showing the main dock itself calls it on its own.
+    pub fn set_focused(&mut self, id: Option<DockAreaId>) {
+        self.focused = id;
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&DockAreaId, &DockAreaState)> {
+        self.areas.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&DockAreaId, &mut DockAreaState)> {
+        self.areas.iter_mut()
+    }
+    /// Adds `tabs` in a floating window of the main dock `state`, which stays in the primary window
+    /// even if a tab is being dragged meanwhile.
+    pub fn add_floating(&mut self, state: &mut DockState<TabId>, tabs: Vec<TabId>) -> SurfaceIndex {
+        let surface = state.add_window(tabs);
+        self.floating.insert(surface);
+        surface
+    }
+    /// A copy of `main` with the tabs of each area bound to a window added as a floating window,
+    /// for layouts saved to disk or to a workspace, which do not keep those windows.
+    pub fn with_window_tabs(&self, main: &DockState<TabId>) -> DockState<TabId> {
+        let mut state = main.clone();
+        for area in self.areas.values().filter(|area| area.window.is_some()) {
+            let tabs: Vec<TabId> = area
+                .state
+                .iter_all_tabs()
+                .map(|(_, tab)| tab.clone())
+                .collect();
+            if !tabs.is_empty() {
+                state.add_window(tabs);
+            }
+        }
+        state
+    }
+    /// Removes the areas bound to a window, returning those windows.
+    pub fn remove_windows(&mut self) -> Vec<Entity> {
+        let windows: Vec<DockAreaId> = self
+            .areas
+            .iter()
+            .filter(|(_, area)| area.window.is_some())
+            .map(|(id, _)| id.clone())
+            .collect();
+        windows
+            .iter()
+            .filter_map(|id| self.remove(id)?.window)
+            .collect()
+    }
+    /// The area containing `tab`.
+    pub fn find_tab(&self, tab: &TabId) -> Option<&DockAreaId> {
+        self.areas
+            .iter()
+            .find(|(_, area)| area.state.find_tab(tab).is_some())
+            .map(|(id, _)| id)
+    }
+}
+
+pub trait DockAreaExt {
+    /// Adds an area of the primary window, shown with [`show_dock_area`].
+    fn add_dock_area(&mut self, id: impl Into<DockAreaId>, tabs: Vec<TabId>) -> &mut Self;
+}
+
+impl DockAreaExt for App {
+    fn add_dock_area(&mut self, id: impl Into<DockAreaId>, tabs: Vec<TabId>) -> &mut Self {
+        self.world_mut().resource_mut::<DockAreas>().insert(
+            id,
+            DockAreaState {
+                state: DockState::new(tabs),
+                window: None,
+            },
+        );
+        self
+    }
+}
+
+/// The dock state containing `tab`, looking in the main dock first.
+pub fn dock_with_tab<'a>(
+    tab: &TabId,
+    main: Option<&'a mut HeDockState>,
+    areas: Option<&'a mut DockAreas>,
+) -> Option<&'a mut DockState<TabId>> {
+    if let Some(main) = main.filter(|main| main.0.find_tab(tab).is_some()) {
+        return Some(&mut main.0);
+    }
+    areas?
+        .areas
+        .values_mut()
+        .map(|area| &mut area.state)
+        .find(|state| state.find_tab(tab).is_some())
+}
+
+fn dock_area_id(id: &DockAreaId) -> Id {
+    Id::new(("dock_area", id.to_string()))
+}
+
+/// Whether a pointer button was pressed inside `rect` this frame.
+pub(crate) fn pressed_inside(ui: &Ui, rect: Rect) -> bool {
+    ui.input(|i| {
+        i.pointer.any_pressed()
+            && i.pointer
+                .press_origin()
+                .is_some_and(|pos| rect.contains(pos))
+    })
+}
+
+/// Shows the area `id` inside `ui`, does nothing if it does not exist.
+pub fn show_dock_area(ui: &mut Ui, world: &mut World, id: &DockAreaId) {
+    world.resource_scope(|world, mut registry: Mut<TabRegistry>| {
+        world.resource_scope(|world, mut areas: Mut<DockAreas>| {
+            let Some(area) = areas.areas.get_mut(id) else {
+                return;
+            };
+            let rect = ui.max_rect();
+            DockArea::new(&mut area.state)
+                .id(dock_area_id(id))
+                .show_inside(
+                    ui,
+                    &mut HeTabViewer {
+                        world,
+                        registry: &mut registry,
+                    },
+                );
+            if pressed_inside(ui, rect) {
+                areas.focused = Some(id.clone());
+            }
+        });
+    });
+}
+
+/// Shows the areas bound to a window other than the primary one, each filling its window.
+pub fn show_window_docks(world: &mut World) {
+    if !world
+        .get_resource::<DockAreas>()
+        .is_some_and(|areas| areas.areas.values().any(|area| area.window.is_some()))
+    {
+        return;
+    }
+    world.resource_scope(|world, mut registry: Mut<TabRegistry>| {
+        world.resource_scope(|world, mut areas: Mut<DockAreas>| {
+            let mut pressed = None;
+            for (id, area) in &mut areas.areas {
+                let Some(ctx) = area
+                    .window
+                    .and_then(|window| world.get_mut::<EguiContext>(window))
+                    .map(|mut context| context.get_mut().clone())
+                else {
+                    continue;
+                };
+                egui::CentralPanel::default().show(&ctx, |ui| {
+                    DockArea::new(&mut area.state)
+                        .id(dock_area_id(id))
+                        .show_inside(
+                            ui,
+                            &mut HeTabViewer {
+                                world,
+                                registry: &mut registry,
+                            },
+                        );
+                    if ui.input(|i| i.pointer.any_pressed()) {
+                        pressed = Some(id.clone());
+                    }
+                });
+            }
+            if pressed.is_some() {
+                areas.focused = pressed;
+            }
+        });
+    });
+}
+
+/// Moves the floating surfaces of the main dock created by dragging a tab out of it into new windows.
+/// Those are the surfaces that were not there the last time, if a drag was in progress or ended in the primary window.
+pub fn tear_off_floating(
+    mut commands: Commands,
+    state: Option<ResMut<HeDockState>>,
+    mut areas: ResMut<DockAreas>,
+    registry: Res<TabRegistry>,
+    mut contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    let Some(mut state) = state else {
+        return;
+    };
+    let has_tabs = |surface: SurfaceIndex| {
+        state
+            .0
+            .get_surface(surface)
+            .and_then(|surface| surface.node_tree())
+            .is_some_and(|tree| tree.num_tabs() > 0)
+    };
+    let mut floating: HashSet<SurfaceIndex> = (1..state.0.surfaces_count())
+        .map(SurfaceIndex)
+        .filter(|surface| has_tabs(*surface))
+        .collect();
+    // egui_dock adds the window on the frame the dragged tab is dropped.
+    let dragging = contexts.get_single_mut().is_ok_and(|mut context| {
+        let ctx = context.get_mut();
+        ctx.dragged_id().is_some() || ctx.drag_stopped_id().is_some()
+    });
+    let mut torn_off: Vec<SurfaceIndex> = if dragging {
+        floating.difference(&areas.floating).copied().collect()
+    } else {
+        Vec::new()
+    };
+    floating.retain(|surface| !torn_off.contains(surface));
+    areas.floating = floating;
+    // Backwards, so that removing the last surface shrinks the list.
+    torn_off.sort_by_key(|surface| std::cmp::Reverse(surface.0));
+    for surface in torn_off {
+        let Some(surface) = state.0.remove_surface(surface) else {
+            continue;
+        };
+        let tabs: Vec<TabId> = surface
+            .iter_all_tabs()
+            .map(|(_, tab)| tab.clone())
+            .collect();
+        let Some(first) = tabs.first() else {
+            continue;
+        };
+        let title = registry
+            .get(first)
+            .map_or_else(|| first.to_string(), |tab| tab.title().into_owned());
+        let window = commands.spawn(Window { title, ..default() }).id();
+        areas.insert(
+            format!("window_{}", window.to_bits()),
+            DockAreaState {
+                state: DockState::new(tabs),
+                window: Some(window),
+            },
+        );
+    }
+}
+
+/// Moves the tabs of the areas whose window was closed back into the main dock.
+pub fn return_closed_windows(
+    mut commands: Commands,
+    state: Option<ResMut<HeDockState>>,
+    mut areas: ResMut<DockAreas>,
+    windows: Query<(), With<Window>>,
+) {
+    let closed: Vec<DockAreaId> = areas
+        .areas
+        .iter()
+        .filter(|(_, area)| area.window.is_some_and(|window| !windows.contains(window)))
+        .map(|(id, _)| id.clone())
+        .collect();
+    if closed.is_empty() {
+        return;
+    }
+    let tabs: Vec<TabId> = closed
+        .iter()
+        .filter_map(|id| areas.remove(id))
+        .flat_map(|area| {
+            area.state
+                .iter_all_tabs()
+                .map(|(_, tab)| tab.clone())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    match state {
+        Some(mut state) => tabs
+            .into_iter()
+            .for_each(|tab| state.0.push_to_first_leaf(tab)),
+        None => commands.insert_resource(HeDockState(DockState::new(tabs))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use egui_dock::NodeIndex;
+
+    use crate::tab_system::{FocusedTab, TabPlugin};
+
+    #[test]
+    fn test_tear_off_and_return() {
+        let mut app = App::new();
+        app.add_plugins(TabPlugin)
+            .insert_resource(HeDockState(DockState::new(vec!["a".into()])))
+            .add_dock_area("bottom", vec!["log".into()]);
+        let ctx = app
+            .world_mut()
+            .spawn((EguiContext::default(), PrimaryWindow))
+            .get_mut::<EguiContext>()
+            .unwrap()
+            .get_mut()
+            .clone();
+        app.update();
+        app.world_mut()
+            .resource_scope(|world, mut state: Mut<HeDockState>| {
+                let mut areas = world.resource_mut::<DockAreas>();
+                areas.add_floating(&mut state.0, vec!["kept".into()]);
+                // what egui_dock does when a dragged tab is dropped outside of the dock
+                state.0.add_window(vec!["b".into(), "c".into()]);
+            });
+        ctx.begin_pass(egui::RawInput::default());
+        ctx.set_dragged_id(Id::new("tab"));
+        app.update();
+        let _ = ctx.end_pass();
+
+        let areas = app.world().resource::<DockAreas>();
+        let torn_off = areas.find_tab(&"b".into()).unwrap();
+        let window = areas.get(torn_off).unwrap().window.unwrap();
+        assert_eq!(areas.find_tab(&"c".into()), Some(torn_off));
+        assert_eq!(areas.find_tab(&"log".into()), Some(&"bottom".into()));
+        let state = &app.world().resource::<HeDockState>().0;
+        assert_eq!(state.find_tab(&"kept".into()).unwrap().0, SurfaceIndex(1));
+        assert_eq!(state.surfaces_count(), 2);
+
+        let saved = areas.with_window_tabs(state);
+        assert_eq!(saved.find_tab(&"c".into()).unwrap().0, SurfaceIndex(2));
+
+        app.world_mut().despawn(window);
+        app.update();
+        let state = &app.world().resource::<HeDockState>().0;
+        assert_eq!(state.main_surface().num_tabs(), 3);
+        assert!(state.find_tab(&"kept".into()).is_some());
+        assert_eq!(app.world().resource::<DockAreas>().iter().count(), 1);
+    }
+
+    #[test]
+    fn test_restored_floating_window() {
+        let mut layout = DockState::new(vec!["a".into()]);
+        layout.add_window(vec!["b".into()]);
+        let mut app = App::new();
+        app.add_plugins(TabPlugin)
+            .insert_resource(HeDockState(layout.clone()));
+        app.update();
+        app.world_mut().resource_mut::<HeDockState>().0 = layout;
+        app.update();
+
+        let state = &app.world().resource::<HeDockState>().0;
+        assert_eq!(state.find_tab(&"b".into()).unwrap().0, SurfaceIndex(1));
+        assert_eq!(app.world().resource::<DockAreas>().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_focused_area() {
+        let mut app = App::new();
+        app.add_plugins(TabPlugin)
+            .insert_resource(HeDockState(DockState::new(vec!["a".into()])))
+            .add_dock_area("bottom", vec!["log".into()]);
+        // what egui_dock does when a tab is clicked
+        let root = (SurfaceIndex::main(), NodeIndex::root());
+        let world = app.world_mut();
+        world
+            .resource_mut::<HeDockState>()
+            .0
+            .set_focused_node_and_surface(root);
+        let mut areas = world.resource_mut::<DockAreas>();
+        let area = areas.get_mut(&"bottom".into()).unwrap();
+        area.state.set_focused_node_and_surface(root);
+        let focused = |app: &App| app.world().resource::<FocusedTab>().0.clone();
+        app.update();
+        assert_eq!(focused(&app), Some("a".into()));
+
+        let mut areas = app.world_mut().resource_mut::<DockAreas>();
+        areas.set_focused(Some("bottom".into()));
+        app.update();
+        assert_eq!(focused(&app), Some("log".into()));
+
+        app.world_mut()
+            .resource_mut::<DockAreas>()
+            .remove(&"bottom".into());
+        app.update();
+        assert_eq!(focused(&app), Some("a".into()));
+    }
+}
@@ -0,0 +1,277 @@
+//! Undo/redo history of actions registered with [`ActionsExt::register_undoable_action`].
+//!
+//! Every run of an undoable action, either instant or through [`Actions::run_action`](crate::action::Actions::run_action),
+//! pushes a record on the [`UndoStack`]. Several records can be grouped with
+//! [`UndoStack::begin_transaction`] and [`UndoStack::end_transaction`] so they are undone together.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    action::{ActionsExt, DynActionStorage},
+    hotkeys::{Hotkey, HotkeysExt, InputKey, Modifier},
+};
+
+pub const UNDO: &str = "edit.undo";
+pub const REDO: &str = "edit.redo";
+
+pub struct UndoRecord {
+    description: String,
+    input: Box<dyn Reflect>,
+    action: Arc<dyn DynActionStorage>,
+    undo: Arc<dyn DynActionStorage>,
+}
+
+impl UndoRecord {
+    pub(crate) fn new(
+        description: String,
+        input: Box<dyn Reflect>,
+        action: Arc<dyn DynActionStorage>,
+        undo: Arc<dyn DynActionStorage>,
+    ) -> Self {
+        Self {
+            description,
+            input,
+            action,
+            undo,
+        }
+    }
+    fn run(&self, storage: &dyn DynActionStorage, world: &mut World) {
+        match storage.get_command(self.input.clone_value()) {
+            Ok(command) => {
+                command(world);
+            }
+            Err(expected) => error!(
+                "cannot replay {}: input type mismatch, expecting {expected}",
+                self.description
+            ),
+        }
+    }
+}
+
+/// A group of records undone and redone as a whole.
+pub struct Transaction {
+    name: String,
+    records: Vec<UndoRecord>,
+}
+
+impl Transaction {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    fn undo(&self, world: &mut World) {
+        for record in self.records.iter().rev() {
+            record.run(&*record.undo, world);
+        }
+    }
+    fn redo(&self, world: &mut World) {
+        for record in &self.records {
+            record.run(&*record.action, world);
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct UndoStack {
+    undo: VecDeque<Transaction>,
+    redo: Vec<Transaction>,
+    limit: usize,
+    open: Option<Transaction>,
+    depth: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: 100,
+            open: None,
+            depth: 0,
+        }
+    }
+}
+
+impl UndoStack {
+    /// The maximum count of transactions kept in history, older ones are dropped first.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.truncate();
+    }
+    /// Starts grouping records into one transaction named `name`.
+    /// Transactions can nest, only the outermost one is kept.
+    pub fn begin_transaction(&mut self, name: impl Into<String>) {
+        if self.depth == 0 {
+            self.open = Some(Transaction {
+                name: name.into(),
+                records: Vec::new(),
+            });
+        }
+        self.depth += 1;
+    }
+    pub fn end_transaction(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            if let Some(transaction) = self.open.take().filter(|t| !t.records.is_empty()) {
+                self.push_transaction(transaction);
+            }
+        }
+    }
+    pub(crate) fn push(&mut self, record: UndoRecord) {
+        match &mut self.open {
+            Some(transaction) => transaction.records.push(record),
+            None => self.push_transaction(Transaction {
+                name: record.description.clone(),
+                records: vec![record],
+            }),
+        }
+    }
+    fn push_transaction(&mut self, transaction: Transaction) {
+        self.redo.clear();
+        self.undo.push_back(transaction);
+        self.truncate();
+    }
+    fn truncate(&mut self) {
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    /// Name of the transaction the next undo reverts.
+    pub fn undo_name(&self) -> Option<&str> {
+        self.undo.back().map(Transaction::name)
+    }
+    /// Name of the transaction the next redo applies.
+    pub fn redo_name(&self) -> Option<&str> {
+        self.redo.last().map(Transaction::name)
+    }
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Reverts the latest transaction. Does nothing if there is none.
+pub fn undo(world: &mut World) {
+    let Some(transaction) = world.resource_mut::<UndoStack>().undo.pop_back() else {
+        return;
+    };
+    transaction.undo(world);
+    world.resource_mut::<UndoStack>().redo.push(transaction);
+}
+
+/// Applies again the latest undone transaction. Does nothing if there is none.
+pub fn redo(world: &mut World) {
+    let Some(transaction) = world.resource_mut::<UndoStack>().redo.pop() else {
+        return;
+    };
+    transaction.redo(world);
+    let mut stack = world.resource_mut::<UndoStack>();
+    stack.undo.push_back(transaction);
+    stack.truncate();
+}
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoStack>()
+            .register_action(UNDO, "Undo", undo)
+            .register_action(REDO, "Redo", redo)
+            .register_hotkey(
+                UNDO,
+                [Hotkey::new_global([
+                    InputKey::from(Modifier::CmdOrCtrl),
+                    KeyCode::KeyZ.into(),
+                ])],
+            )
+            .register_hotkey(
+                REDO,
+                [Hotkey::new_global([
+                    InputKey::from(Modifier::CmdOrCtrl),
+                    KeyCode::KeyY.into(),
+                ])],
+            );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        action::{ActionPlugin, ActionRegistry},
+        hotkeys::HotkeyPlugin,
+    };
+
+    #[derive(Resource, Default)]
+    struct Counter(i32);
+
+    fn add(In(value): In<i32>, mut counter: ResMut<Counter>) {
+        counter.0 += value;
+    }
+
+    fn sub(In(value): In<i32>, mut counter: ResMut<Counter>) {
+        counter.0 -= value;
+    }
+
+    fn add_positive(In(value): In<i32>, mut counter: ResMut<Counter>) -> Result<(), String> {
+        if value <= 0 {
+            return Err(format!("{value} is not positive"));
+        }
+        counter.0 += value;
+        Ok(())
+    }
+
+    fn run(world: &mut World, id: &str, value: i32) {
+        world.resource_scope(|world, mut actions: Mut<ActionRegistry>| {
+            actions.run_instant(&id.into(), value, world).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, HotkeyPlugin, UndoPlugin))
+            .init_resource::<Counter>()
+            .register_undoable_action("counter.add", "Add", add, sub)
+            .register_undoable_action("counter.add_positive", "Add positive", add_positive, sub);
+        let world = app.world_mut();
+
+        run(world, "counter.add", 1);
+        world
+            .resource_mut::<UndoStack>()
+            .begin_transaction("Add twice");
+        run(world, "counter.add", 2);
+        run(world, "counter.add", 3);
+        world.resource_mut::<UndoStack>().end_transaction();
+        assert_eq!(world.resource::<Counter>().0, 6);
+        assert_eq!(world.resource::<UndoStack>().undo_name(), Some("Add twice"));
+
+        undo(world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+        undo(world);
+        assert_eq!(world.resource::<Counter>().0, 0);
+        assert!(!world.resource::<UndoStack>().can_undo());
+        redo(world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+
+        run(world, "counter.add", 4);
+        assert!(!world.resource::<UndoStack>().can_redo());
+        run(world, "counter.add_positive", -1);
+        assert_eq!(world.resource::<UndoStack>().undo_name(), Some("Add"));
+
+        world.resource_mut::<UndoStack>().set_limit(1);
+        undo(world);
+        undo(world);
+        assert_eq!(world.resource::<Counter>().0, 1);
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::{ecs::schedule::BoxedCondition, prelude::{Condition, IntoSystem, System}};
 
+pub mod fuzzy;
 pub mod identifier;
 
 pub fn new_condition<M>(condition: impl Condition<M>) -> BoxedCondition {
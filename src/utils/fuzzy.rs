@@ -0,0 +1,56 @@
+//! Subsequence based fuzzy matching, used by searchable lists such as the command palette.
+
+/// Scores how well `pattern` matches `candidate`, higher is better.
+///
+/// Returns `None` if `pattern` is not a case-insensitive subsequence of `candidate`.
+/// Consecutive matches and matches at the start of a word (after `.`, `_`, `-`, a space
+/// or a lowercase to uppercase change) are preferred.
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<i64> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut first_match = None;
+    let mut last_match = None;
+    let mut prev: Option<char> = None;
+    for (index, c) in candidate.chars().enumerate() {
+        let Some(&expected) = pattern.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(expected)) {
+            pattern.next();
+            score += 1;
+            if last_match.is_some_and(|last| last + 1 == index) {
+                score += 5;
+            }
+            let boundary = match prev {
+                None => true,
+                Some(p) => {
+                    matches!(p, '.' | '_' | '-' | ' ') || (p.is_lowercase() && c.is_uppercase())
+                }
+            };
+            if boundary {
+                score += 8;
+            }
+            first_match.get_or_insert(index);
+            last_match = Some(index);
+        }
+        prev = Some(c);
+    }
+    if pattern.peek().is_some() {
+        return None;
+    }
+    Some(score - first_match.unwrap_or(0).min(10) as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("xyz", "palette.show").is_none());
+        assert!(fuzzy_score("PS", "palette.show").is_some());
+        assert!(fuzzy_score("ps", "palette.show") > fuzzy_score("ps", "maps"));
+        assert!(fuzzy_score("fs", "file.save") > fuzzy_score("fs", "buffers"));
+    }
+}
@@ -6,11 +6,14 @@ use std::{
     str::FromStr,
 };
 
-use bevy::prelude::Deref;
+use bevy::{prelude::Deref, reflect::Reflect};
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-#[derive(Deref, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
+/// Serialized as the dotted string. Reflected as an opaque value so it can be passed to actions.
+#[derive(Deref, Hash, PartialEq, Eq, Clone, Serialize, Deserialize, Reflect)]
+#[serde(from = "String", into = "String")]
+#[reflect_value(Hash, PartialEq)]
 pub struct Identifier {
     inner: SmallVec<[String; 6]>,
 }
@@ -49,6 +52,18 @@ impl From<&str> for Identifier {
     }
 }
 
+impl From<String> for Identifier {
+    fn from(s: String) -> Self {
+        s.as_str().into()
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(id: Identifier) -> Self {
+        id.to_string()
+    }
+}
+
 impl Display for Identifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&self.inner.join("."))
@@ -74,4 +89,8 @@ impl Identifier {
     pub fn pop(&mut self) -> Option<String> {
         self.inner.pop()
     }
+    /// Whether the first segments are those of `prefix`, `a.b.c` starts with `a.b` but not `a.bc`.
+    pub fn starts_with(&self, prefix: &Identifier) -> bool {
+        self.inner.starts_with(&prefix.inner)
+    }
 }
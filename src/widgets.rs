@@ -1,5 +1,11 @@
+mod argument_editor;
 mod dock_buttons;
+mod keybinding_editor;
+mod workspace_menu;
+pub use argument_editor::*;
 pub use dock_buttons::*;
+pub use keybinding_editor::*;
+pub use workspace_menu::*;
 
 use bevy::prelude::*;
 use egui::Ui;
@@ -0,0 +1,366 @@
+//! Forms generated from [`TypeInfo`], used to call actions that take an input.
+//!
+//! Field types of the edited argument must be registered in the [`AppTypeRegistry`]
+//! (`app.register_type::<T>()`), the argument type itself does not need to be.
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicStruct, DynamicTuple,
+        DynamicTupleStruct, DynamicVariant, ReflectMut, ReflectRef, TypeInfo, TypeRegistry,
+        VariantInfo,
+    },
+};
+use egui::{ComboBox, DragValue, TextEdit, Ui};
+use rust_i18n::t;
+
+use crate::action::{ActionError, ActionId, ActionRegistry};
+
+/// Builds a default value for the type described by `info`.
+///
+/// [`ReflectDefault`] is used if the type registered it,
+/// otherwise a dynamic value is built field by field. Enums start at their first variant.
+/// Returns `None` if some field type is not registered and has no default.
+pub fn default_value(info: &'static TypeInfo, registry: &TypeRegistry) -> Option<Box<dyn Reflect>> {
+    if let Some(default) = registry.get_type_data::<ReflectDefault>(info.type_id()) {
+        return Some(default.default());
+    }
+    let field_default = |type_id| default_value(registry.get_type_info(type_id)?, registry);
+    let mut value: Box<dyn Reflect> = match info {
+        TypeInfo::Struct(info) => {
+            let mut value = DynamicStruct::default();
+            for field in info.iter() {
+                value.insert_boxed(field.name(), field_default(field.type_id())?);
+            }
+            Box::new(value)
+        }
+        TypeInfo::TupleStruct(info) => {
+            let mut value = DynamicTupleStruct::default();
+            for field in info.iter() {
+                value.insert_boxed(field_default(field.type_id())?);
+            }
+            Box::new(value)
+        }
+        TypeInfo::Tuple(info) => {
+            let mut value = DynamicTuple::default();
+            for field in info.iter() {
+                value.insert_boxed(field_default(field.type_id())?);
+            }
+            Box::new(value)
+        }
+        TypeInfo::List(_) => Box::<DynamicList>::default(),
+        TypeInfo::Map(_) => Box::<DynamicMap>::default(),
+        TypeInfo::Array(array) => Box::new(DynamicArray::new(
+            (0..array.capacity())
+                .map(|_| field_default(array.item_type_id()))
+                .collect::<Option<_>>()?,
+        )),
+        TypeInfo::Enum(info) => Box::new(default_variant(info.variant_at(0)?, registry)?),
+        TypeInfo::Value(_) => return None,
+    };
+    set_represented_type(value.as_mut(), info);
+    Some(value)
+}
+
+fn set_represented_type(value: &mut dyn Reflect, info: &'static TypeInfo) {
+    if let Some(value) = value.downcast_mut::<DynamicStruct>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicTupleStruct>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicTuple>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicList>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicMap>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicArray>() {
+        value.set_represented_type(Some(info));
+    } else if let Some(value) = value.downcast_mut::<DynamicEnum>() {
+        value.set_represented_type(Some(info));
+    }
+}
+
+fn default_variant(variant: &VariantInfo, registry: &TypeRegistry) -> Option<DynamicEnum> {
+    let field_default = |type_id| default_value(registry.get_type_info(type_id)?, registry);
+    let dynamic_variant = match variant {
+        VariantInfo::Struct(info) => {
+            let mut value = DynamicStruct::default();
+            for field in info.iter() {
+                value.insert_boxed(field.name(), field_default(field.type_id())?);
+            }
+            DynamicVariant::Struct(value)
+        }
+        VariantInfo::Tuple(info) => {
+            let mut value = DynamicTuple::default();
+            for field in info.iter() {
+                value.insert_boxed(field_default(field.type_id())?);
+            }
+            DynamicVariant::Tuple(value)
+        }
+        VariantInfo::Unit(_) => DynamicVariant::Unit,
+    };
+    Some(DynamicEnum::new(variant.name(), dynamic_variant))
+}
+
+/// Draws an editor for `value`, returns whether it has been changed.
+pub fn reflect_editor_ui(ui: &mut Ui, value: &mut dyn Reflect, registry: &TypeRegistry) -> bool {
+    let mut changed = false;
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for i in 0..value.field_len() {
+                let name = value.name_at(i).unwrap_or_default().to_owned();
+                let field = value.field_at_mut(i).unwrap();
+                changed |= ui
+                    .push_id(i, |ui| field_ui(ui, &name, field, registry))
+                    .inner;
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for i in 0..value.field_len() {
+                let field = value.field_mut(i).unwrap();
+                changed |= ui
+                    .push_id(i, |ui| field_ui(ui, &i.to_string(), field, registry))
+                    .inner;
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for i in 0..value.field_len() {
+                let field = value.field_mut(i).unwrap();
+                changed |= ui
+                    .push_id(i, |ui| field_ui(ui, &i.to_string(), field, registry))
+                    .inner;
+            }
+        }
+        ReflectMut::Array(value) => {
+            for i in 0..value.len() {
+                let item = value.get_mut(i).unwrap();
+                changed |= ui
+                    .push_id(i, |ui| field_ui(ui, &i.to_string(), item, registry))
+                    .inner;
+            }
+        }
+        ReflectMut::List(value) => {
+            let mut to_remove = None;
+            for i in 0..value.len() {
+                let item = value.get_mut(i).unwrap();
+                ui.push_id(i, |ui| {
+                    ui.horizontal_top(|ui| {
+                        if ui.small_button("-").clicked() {
+                            to_remove = Some(i);
+                        }
+                        ui.vertical(|ui| changed |= reflect_editor_ui(ui, item, registry));
+                    })
+                });
+            }
+            if let Some(i) = to_remove {
+                value.remove(i);
+                changed = true;
+            }
+            let new_item = match value.get_represented_type_info() {
+                Some(TypeInfo::List(info)) => registry
+                    .get_type_info(info.item_type_id())
+                    .and_then(|info| default_value(info, registry)),
+                _ => None,
+            };
+            if let Some(new_item) = new_item {
+                if ui.small_button(t!("editor.add_item")).clicked() {
+                    value.push(new_item);
+                    changed = true;
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() else {
+                ui.weak(value.reflect_type_path());
+                return false;
+            };
+            let mut selected = value.variant_name().to_owned();
+            ComboBox::from_id_salt("variant")
+                .selected_text(&selected)
+                .show_ui(ui, |ui| {
+                    for name in info.variant_names() {
+                        ui.selectable_value(&mut selected, name.to_string(), *name);
+                    }
+                });
+            if selected != value.variant_name() {
+                if let Some(mut new_value) = info
+                    .variant(&selected)
+                    .and_then(|variant| default_variant(variant, registry))
+                {
+                    new_value.set_represented_type(value.get_represented_type_info());
+                    value.apply(&new_value);
+                    changed = true;
+                }
+            }
+            ui.indent("variant_fields", |ui| {
+                for i in 0..value.field_len() {
+                    let name = value
+                        .name_at(i)
+                        .map_or_else(|| i.to_string(), str::to_owned);
+                    let field = value.field_at_mut(i).unwrap();
+                    changed |= ui
+                        .push_id(i, |ui| field_ui(ui, &name, field, registry))
+                        .inner;
+                }
+            });
+        }
+        ReflectMut::Map(value) => {
+            ui.weak(t!("editor.unsupported", ty = value.reflect_type_path()));
+        }
+        ReflectMut::Value(value) => changed |= value_ui(ui, value),
+    }
+    changed
+}
+
+fn field_ui(ui: &mut Ui, name: &str, value: &mut dyn Reflect, registry: &TypeRegistry) -> bool {
+    if let ReflectRef::Value(_) = value.reflect_ref() {
+        ui.horizontal(|ui| {
+            ui.label(name);
+            reflect_editor_ui(ui, value, registry)
+        })
+        .inner
+    } else {
+        ui.label(name);
+        ui.indent(name, |ui| reflect_editor_ui(ui, value, registry))
+            .inner
+    }
+}
+
+fn value_ui(ui: &mut Ui, value: &mut dyn Reflect) -> bool {
+    macro_rules! numeric {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_mut::<$ty>() {
+                    return ui.add(DragValue::new(value)).changed();
+                }
+            )*
+        };
+    }
+    numeric!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    if let Some(value) = value.downcast_mut::<bool>() {
+        return ui.checkbox(value, "").changed();
+    }
+    if let Some(value) = value.downcast_mut::<String>() {
+        return ui.text_edit_singleline(value).changed();
+    }
+    if let Some(value) = value.downcast_mut::<char>() {
+        let mut text = value.to_string();
+        let changed = ui
+            .add(TextEdit::singleline(&mut text).char_limit(1))
+            .changed();
+        if let Some(c) = text.chars().next() {
+            *value = c;
+        }
+        return changed;
+    }
+    ui.weak(t!("editor.unsupported", ty = value.reflect_type_path()));
+    false
+}
+
+/// A form for the input of an action.
+pub struct ArgumentEditor {
+    action: ActionId,
+    input_info: &'static TypeInfo,
+    value: Option<Box<dyn Reflect>>,
+}
+
+impl ArgumentEditor {
+    /// Returns `None` if the action does not exist.
+    pub fn new(
+        action: ActionId,
+        actions: &ActionRegistry,
+        registry: &TypeRegistry,
+    ) -> Option<Self> {
+        let input_info = actions.get(&action)?.input_type_info();
+        Some(Self {
+            value: default_value(input_info, registry),
+            input_info,
+            action,
+        })
+    }
+    pub fn action(&self) -> &ActionId {
+        &self.action
+    }
+    /// The current value of the form, or `None` if the input type is not supported.
+    pub fn value(&self) -> Option<&dyn Reflect> {
+        self.value.as_deref()
+    }
+    /// Draws the form and a run button, returns a copy of the input when the button is clicked.
+    pub fn ui(&mut self, ui: &mut Ui, registry: &TypeRegistry) -> Option<Box<dyn Reflect>> {
+        let Some(value) = &mut self.value else {
+            ui.weak(t!("editor.unsupported", ty = self.input_info.type_path()));
+            return None;
+        };
+        reflect_editor_ui(ui, value.as_mut(), registry);
+        ui.button(t!("editor.run"))
+            .clicked()
+            .then(|| value.clone_value())
+    }
+    /// Runs the action with the current value of the form.
+    /// Returns `None` if the input type is not supported.
+    pub fn run(&self, world: &mut World) -> Option<Result<Box<dyn Reflect>, ActionError>> {
+        let input = self.value.as_ref()?.clone_value();
+        Some(
+            world.resource_scope(|world, mut actions: Mut<ActionRegistry>| {
+                actions.run_instant_dyn(&self.action, input, world)
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::reflect::{FromReflect, Typed};
+
+    use super::*;
+
+    #[derive(Reflect, Debug, PartialEq)]
+    enum Mode {
+        Simple,
+        Repeat(u32),
+    }
+
+    #[derive(Reflect, Debug, PartialEq)]
+    struct Argument {
+        name: String,
+        count: u32,
+        mode: Mode,
+        scales: Vec<f32>,
+        limit: Option<u8>,
+    }
+
+    #[test]
+    fn test_default_value() {
+        let mut registry = TypeRegistry::new();
+        registry.register::<String>();
+        registry.register::<u32>();
+        registry.register::<Mode>();
+        registry.register::<Vec<f32>>();
+        registry.register::<Option<u8>>();
+
+        let mut value = default_value(Argument::type_info(), &registry).unwrap();
+        assert_eq!(
+            Argument::from_reflect(&*value),
+            Some(Argument {
+                name: String::new(),
+                count: 0,
+                mode: Mode::Simple,
+                scales: Vec::new(),
+                limit: None,
+            })
+        );
+
+        let ReflectMut::Struct(fields) = value.reflect_mut() else {
+            panic!("expected a struct");
+        };
+        let TypeInfo::Enum(mode) = Mode::type_info() else {
+            panic!("expected an enum");
+        };
+        let repeat = default_variant(mode.variant("Repeat").unwrap(), &registry).unwrap();
+        fields.field_mut("mode").unwrap().apply(&repeat);
+        assert_eq!(
+            Argument::from_reflect(&*value).map(|argument| argument.mode),
+            Some(Mode::Repeat(0))
+        );
+    }
+}
@@ -1,17 +1,26 @@
 use bevy::prelude::*;
 
-use crate::{tab_system::TabRegistry, prelude::HeDockState};
+use crate::{
+    prelude::HeDockState,
+    tab_system::{
+        areas::{dock_with_tab, DockAreas},
+        TabRegistry,
+    },
+};
 
-pub fn dock_button(In(ui): In<&'static mut egui::Ui>, mut state: ResMut<HeDockState>, registry: Res<TabRegistry>) {
-    let state = &mut state.0;
-    let opened: Vec<_> = state.iter_all_tabs().map(|i| i.1).collect();
+pub fn dock_button(
+    In(ui): In<&'static mut egui::Ui>,
+    mut state: ResMut<HeDockState>,
+    mut areas: ResMut<DockAreas>,
+    registry: Res<TabRegistry>,
+) {
     let mut to_remove = None;
     let mut to_add = None;
     for (i, tab) in registry.iter() {
-        let is_opened = opened.contains(&i);
+        let is_opened = state.0.find_tab(i).is_some() || areas.find_tab(i).is_some();
         if ui.selectable_label(is_opened, tab.title()).clicked() {
             if is_opened {
-                to_remove = Some(state.find_tab(i).expect("i is opened but then not found?"));
+                to_remove = Some(i.clone());
                 ui.close_menu();
             } else {
                 to_add = Some(i.clone());
@@ -20,9 +29,13 @@ pub fn dock_button(In(ui): In<&'static mut egui::Ui>, mut state: ResMut<HeDockSt
         }
     }
     if let Some(to) = to_remove {
-        state.remove_tab(to);
+        let dock = dock_with_tab(&to, Some(&mut state), Some(&mut areas))
+            .expect("i is opened but then not found?");
+        if let Some(index) = dock.find_tab(&to) {
+            dock.remove_tab(index);
+        }
     }
     if let Some(to) = to_add {
-        state.add_window(vec![to]);
+        areas.add_floating(&mut state.0, vec![to]);
     }
 }
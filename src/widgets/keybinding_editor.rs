@@ -0,0 +1,149 @@
+use bevy::{prelude::*, utils::HashSet};
+use egui::{Color32, RichText, Ui};
+use rust_i18n::t;
+
+use crate::{
+    action::{ActionId, ActionRegistry},
+    hotkeys::{recorder::HotkeyRecorder, Hotkey, HotkeyRegistry, KeyBinding},
+    utils::fuzzy::fuzzy_score,
+};
+
+/// A tab listing every action with its hotkeys, register it with
+/// [`TabRegistrationExt::register_tab`](crate::tab_system::TabRegistrationExt::register_tab).
+///
+/// Clicking a binding records a new key combination into it, bindings shared by several actions are shown in red.
+pub fn keybinding_editor(
+    In(mut ui): In<Ui>,
+    actions: Res<ActionRegistry>,
+    mut hotkeys: ResMut<HotkeyRegistry>,
+    mut recorder: ResMut<HotkeyRecorder>,
+    mut query: Local<String>,
+) {
+    let conflicts = hotkeys.conflicts();
+    let conflicting: HashSet<&KeyBinding> = conflicts.iter().map(|c| &c.binding).collect();
+
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut *query).hint_text(t!("keybinding.search_hint")));
+        if ui.button(t!("keybinding.reset_all")).clicked() {
+            recorder.cancel();
+            hotkeys.reset_to_defaults();
+        }
+    });
+    for conflict in &conflicts {
+        let actions = conflict
+            .actions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        ui.colored_label(
+            Color32::RED,
+            t!(
+                "keybinding.conflict",
+                binding = conflict.binding.text(),
+                actions = actions
+            ),
+        );
+    }
+    ui.separator();
+
+    let mut entries: Vec<_> = actions
+        .iter()
+        .filter_map(|(id, storage)| {
+            let score = fuzzy_score(&query, &id.to_string())
+                .max(fuzzy_score(&query, storage.get_description()))?;
+            Some((score, id.clone(), storage.get_description().to_owned()))
+        })
+        .collect();
+    entries.sort_by(|(score1, id1, _), (score2, id2, _)| {
+        score2
+            .cmp(score1)
+            .then_with(|| id1.to_string().cmp(&id2.to_string()))
+    });
+
+    let mut reset = None;
+    let mut remove = None;
+    egui::ScrollArea::vertical().show(&mut ui, |ui| {
+        egui::Grid::new("keybinding_editor")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (_, id, description) in &entries {
+                    ui.label(description).on_hover_text(id.to_string());
+                    let listeners = hotkeys.get(id).map(|l| l.as_slice()).unwrap_or_default();
+                    ui.horizontal_wrapped(|ui| {
+                        for (index, hotkey) in listeners.iter().enumerate() {
+                            if binding_button(ui, id, index, hotkey, &conflicting, &mut recorder) {
+                                remove = Some((id.clone(), index));
+                            }
+                        }
+                        let adding = recorder.target() == Some((id, None));
+                        let add_text = if adding {
+                            t!("keybinding.recording")
+                        } else {
+                            "+".into()
+                        };
+                        if ui
+                            .selectable_label(adding, add_text)
+                            .on_hover_text(t!("keybinding.add"))
+                            .clicked()
+                        {
+                            recorder.start(id.clone(), None);
+                        }
+                    });
+                    if ui
+                        .add_enabled(
+                            listeners.iter().any(Hotkey::is_modified),
+                            egui::Button::new(t!("keybinding.reset")),
+                        )
+                        .clicked()
+                    {
+                        reset = Some(id.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+    if let Some(id) = reset {
+        recorder.cancel();
+        hotkeys.reset_action(&id);
+    }
+    if let Some((id, index)) = remove {
+        recorder.cancel();
+        hotkeys.remove_binding(&id, index);
+    }
+}
+
+/// Returns whether the binding should be removed.
+fn binding_button(
+    ui: &mut Ui,
+    id: &ActionId,
+    index: usize,
+    hotkey: &Hotkey,
+    conflicting: &HashSet<&KeyBinding>,
+    recorder: &mut HotkeyRecorder,
+) -> bool {
+    let recording = recorder.target() == Some((id, Some(index)));
+    let text = if recording {
+        RichText::new(t!("keybinding.recording"))
+    } else if hotkey.binding.is_empty() {
+        RichText::new(t!("keybinding.unbound")).italics().weak()
+    } else if conflicting.contains(&hotkey.binding) {
+        RichText::new(hotkey.hotkey_text()).color(Color32::RED)
+    } else {
+        RichText::new(hotkey.hotkey_text())
+    };
+    let response = ui.selectable_label(recording, text);
+    if response.clicked() {
+        if recording {
+            recorder.cancel();
+        } else {
+            recorder.start(id.clone(), Some(index));
+        }
+    }
+    !hotkey.binding.is_empty()
+        && ui
+            .small_button("×")
+            .on_hover_text(t!("keybinding.remove"))
+            .clicked()
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+
+use crate::{
+    action::{ActionId, Actions},
+    workspace::{Workspaces, DELETE_WORKSPACE, SAVE_WORKSPACE_AS, SWITCH_WORKSPACE},
+};
+
+/// Lists the [`Workspaces`], clicking one switches to it.
+/// Also allows saving the current layout under a new name and deleting the current workspace.
+pub fn workspace_menu(
+    In(ui): In<&'static mut egui::Ui>,
+    workspaces: Res<Workspaces>,
+    mut actions: Actions,
+    mut new_name: Local<String>,
+) {
+    let mut run = |id: &str, name: String| {
+        if let Err(err) = actions.run_action(&ActionId::from(id), name) {
+            error!("encountered error when running action: {}", err);
+        }
+    };
+    for name in workspaces.names() {
+        if ui
+            .selectable_label(workspaces.current() == Some(name), name)
+            .clicked()
+        {
+            run(SWITCH_WORKSPACE, name.to_owned());
+            ui.close_menu();
+        }
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut *new_name)
+                .hint_text(t!("workspace.name_hint"))
+                .desired_width(120.0),
+        );
+        if ui
+            .add_enabled(
+                !new_name.is_empty(),
+                egui::Button::new(t!("workspace.save_as")),
+            )
+            .clicked()
+        {
+            run(SAVE_WORKSPACE_AS, std::mem::take(&mut *new_name));
+            ui.close_menu();
+        }
+    });
+    if let Some(current) = workspaces.current() {
+        if ui.button(t!("workspace.delete", name = current)).clicked() {
+            run(DELETE_WORKSPACE, current.to_owned());
+            ui.close_menu();
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Named dock layouts ("perspectives") the user can switch between.
+//!
+//! Switching only replaces the [`HeDockState`], tab systems stay in the [`TabRegistry`](crate::tab_system::TabRegistry),
+//! so a tab present in both layouts keeps its state.
+//! The tabs torn off into their own window are stored as floating windows of the layout, and their windows closed on switch.
+
+use bevy::prelude::*;
+use egui_dock::DockState;
+use indexmap::IndexMap;
+use snafu::Snafu;
+
+use crate::{
+    action::ActionsExt,
+    tab_system::{areas::DockAreas, HeDockState, TabId},
+};
+
+pub const SWITCH_WORKSPACE: &str = "workspace.switch";
+pub const SAVE_WORKSPACE_AS: &str = "workspace.save_as";
+pub const DELETE_WORKSPACE: &str = "workspace.delete";
+
+#[derive(Resource, Default)]
+pub struct Workspaces {
+    layouts: IndexMap<String, DockState<TabId>>,
+    current: Option<String>,
+}
+
+impl Workspaces {
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layouts.keys().map(String::as_str)
+    }
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+    pub fn get(&self, name: &str) -> Option<&DockState<TabId>> {
+        self.layouts.get(name)
+    }
+    /// Adds or replaces the workspace `name`.
+    pub fn insert(&mut self, name: impl Into<String>, layout: DockState<TabId>) {
+        self.layouts.insert(name.into(), layout);
+    }
+    pub fn remove(&mut self, name: &str) -> Result<DockState<TabId>, WorkspaceError> {
+        if self.current.as_deref() == Some(name) {
+            self.current = None;
+        }
+        self.layouts
+            .shift_remove(name)
+            .ok_or_else(|| WorkspaceError::NotFound { name: name.into() })
+    }
+    /// Stores `layout` into the current workspace, then makes `name` current and returns its layout.
+    pub fn switch(
+        &mut self,
+        name: &str,
+        layout: Option<&DockState<TabId>>,
+    ) -> Result<DockState<TabId>, WorkspaceError> {
+        let new_layout = self
+            .layouts
+            .get(name)
+            .ok_or_else(|| WorkspaceError::NotFound { name: name.into() })?
+            .clone();
+        if let (Some(current), Some(layout)) = (&self.current, layout) {
+            if let Some(stored) = self.layouts.get_mut(current) {
+                *stored = layout.clone();
+            }
+        }
+        self.current = Some(name.to_owned());
+        Ok(new_layout)
+    }
+}
+
+#[derive(Snafu, Debug)]
+pub enum WorkspaceError {
+    #[snafu(display("Workspace {name} does not exist."))]
+    NotFound { name: String },
+}
+
+pub trait WorkspaceExt {
+    fn register_workspace(
+        &mut self,
+        name: impl Into<String>,
+        layout: DockState<TabId>,
+    ) -> &mut Self;
+}
+
+impl WorkspaceExt for App {
+    fn register_workspace(
+        &mut self,
+        name: impl Into<String>,
+        layout: DockState<TabId>,
+    ) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<Workspaces>()
+            .insert(name, layout);
+        self
+    }
+}
+
+fn switch_workspace(
+    In(name): In<String>,
+    mut commands: Commands,
+    mut workspaces: ResMut<Workspaces>,
+    state: Option<ResMut<HeDockState>>,
+    areas: Option<ResMut<DockAreas>>,
+) -> Result<(), String> {
+    let current = state.as_deref().map(|state| match areas.as_deref() {
+        Some(areas) => areas.with_window_tabs(&state.0),
+        None => state.0.clone(),
+    });
+    let layout = workspaces
+        .switch(&name, current.as_ref())
+        .map_err(|err| err.to_string())?;
+    // Their tabs are part of the stored layout now.
+    if let Some(mut areas) = areas {
+        for window in areas.remove_windows() {
+            commands.entity(window).despawn();
+        }
+    }
+    match state {
+        Some(mut state) => state.0 = layout,
+        None => commands.insert_resource(HeDockState(layout)),
+    }
+    Ok(())
+}
+
+fn save_workspace_as(
+    In(name): In<String>,
+    mut workspaces: ResMut<Workspaces>,
+    state: Option<Res<HeDockState>>,
+    areas: Option<Res<DockAreas>>,
+) -> Result<(), String> {
+    let state = state.ok_or("no dock layout to save")?;
+    let layout = match areas {
+        Some(areas) => areas.with_window_tabs(&state.0),
+        None => state.0.clone(),
+    };
+    workspaces.insert(name.clone(), layout);
+    workspaces.current = Some(name);
+    Ok(())
+}
+
+fn delete_workspace(
+    In(name): In<String>,
+    mut workspaces: ResMut<Workspaces>,
+) -> Result<(), String> {
+    workspaces
+        .remove(&name)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+pub struct WorkspacePlugin;
+
+impl Plugin for WorkspacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Workspaces>()
+            .register_action(SWITCH_WORKSPACE, "Switch workspace", switch_workspace)
+            .register_action(
+                SAVE_WORKSPACE_AS,
+                "Save layout as workspace",
+                save_workspace_as,
+            )
+            .register_action(DELETE_WORKSPACE, "Delete workspace", delete_workspace);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        action::{failure_message, ActionPlugin, ActionRegistry},
+        tab_system::areas::DockAreaState,
+    };
+
+    /// Runs a workspace action, returning its error message if it failed.
+    fn run(world: &mut World, id: &str, name: &str) -> Option<String> {
+        world.resource_scope(|world, mut actions: Mut<ActionRegistry>| {
+            let output = actions
+                .run_instant(&id.into(), name.to_owned(), world)
+                .unwrap();
+            failure_message(&*output)
+        })
+    }
+
+    fn tabs(world: &World) -> Vec<TabId> {
+        world
+            .resource::<HeDockState>()
+            .0
+            .iter_all_tabs()
+            .map(|(_, tab)| tab.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_switch_and_save() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, WorkspacePlugin))
+            .register_workspace("code", DockState::new(vec!["editor".into()]))
+            .register_workspace("debug", DockState::new(vec!["console".into()]));
+        let world = app.world_mut();
+
+        assert_eq!(run(world, SWITCH_WORKSPACE, "code"), None);
+        assert_eq!(tabs(world), [TabId::from("editor")]);
+        world
+            .resource_mut::<HeDockState>()
+            .0
+            .push_to_first_leaf("outline".into());
+        let window = world.spawn_empty().id();
+        world.init_resource::<DockAreas>();
+        world.resource_mut::<DockAreas>().insert(
+            "torn_off",
+            DockAreaState {
+                state: DockState::new(vec!["log".into()]),
+                window: Some(window),
+            },
+        );
+        assert_eq!(run(world, SWITCH_WORKSPACE, "debug"), None);
+        assert_eq!(tabs(world), [TabId::from("console")]);
+        assert_eq!(world.resource::<DockAreas>().iter().count(), 0);
+        assert!(world.get_entity(window).is_none());
+        // The changes to the layout of "code" were stored when leaving it, torn off tabs included.
+        assert_eq!(run(world, SWITCH_WORKSPACE, "code"), None);
+        assert_eq!(
+            tabs(world),
+            [TabId::from("editor"), "outline".into(), "log".into()]
+        );
+
+        assert_eq!(run(world, SAVE_WORKSPACE_AS, "review"), None);
+        let workspaces = world.resource::<Workspaces>();
+        assert_eq!(workspaces.current(), Some("review"));
+        assert_eq!(
+            workspaces.names().collect::<Vec<_>>(),
+            ["code", "debug", "review"]
+        );
+        assert!(run(world, SWITCH_WORKSPACE, "missing").is_some());
+        assert_eq!(world.resource::<Workspaces>().current(), Some("review"));
+    }
+
+    #[test]
+    fn test_delete_current() {
+        let mut app = App::new();
+        app.add_plugins((ActionPlugin, WorkspacePlugin))
+            .register_workspace("code", DockState::new(vec!["editor".into()]))
+            .register_workspace("debug", DockState::new(vec!["console".into()]));
+        let world = app.world_mut();
+
+        assert_eq!(run(world, SWITCH_WORKSPACE, "code"), None);
+        assert_eq!(run(world, DELETE_WORKSPACE, "code"), None);
+        let workspaces = world.resource::<Workspaces>();
+        assert_eq!(workspaces.current(), None);
+        assert_eq!(workspaces.names().collect::<Vec<_>>(), ["debug"]);
+        // The layout stays shown, it is just not stored anywhere anymore.
+        assert_eq!(tabs(world), [TabId::from("editor")]);
+        assert!(run(world, DELETE_WORKSPACE, "code").is_some());
+
+        assert_eq!(run(world, SWITCH_WORKSPACE, "debug"), None);
+        assert_eq!(tabs(world), [TabId::from("console")]);
+        assert!(world.resource::<Workspaces>().get("code").is_none());
+    }
+}